@@ -13,8 +13,11 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::data::clipboard::ClipboardRepository;
+use crate::data::font_file::FontFileRepository;
 use crate::data::font_list::FontListRepository;
 use crate::data::preferences::PreferencesRepository;
+use crate::data::sample_text::SampleTextRepository;
 use crate::feature::main::{MainView, MainViewCommand};
 use crate::feature::settings::{SettingsView, SettingsViewCommand};
 use crate::feature::toolbar::{Toolbar, ToolbarCommand};
@@ -39,13 +42,29 @@ pub fn application() -> Application<impl Program<State = AppState, Message = App
 fn boot() -> (AppState, Task<AppCommand>) {
     let project_dirs = directories::ProjectDirs::from("com", "sukawasatoru", "Fonts66 Viewer")
         .expect("no valid home directory");
-    let font_list_repo = Arc::new(FontListRepository::default());
+    let font_file_repo = Arc::new(FontFileRepository::default());
+    let font_list_repo = Arc::new(FontListRepository::new(&project_dirs));
     let prefs_repo = Arc::new(PreferencesRepository::new(&project_dirs));
+    let sample_text_repo = Arc::new(SampleTextRepository::new(&project_dirs));
+    let export_dir = directories::UserDirs::new()
+        .and_then(|dirs| dirs.download_dir().map(|dir| dir.to_path_buf()))
+        .unwrap_or_else(|| project_dirs.data_dir().join("exports"));
 
     let state = AppState {
         expand_settings: false,
-        view_main: MainView::new(),
-        view_settings: SettingsView::new(font_list_repo, prefs_repo),
+        view_main: MainView::new(
+            font_list_repo.clone(),
+            font_file_repo.clone(),
+            Arc::new(ClipboardRepository::default()),
+            export_dir.clone(),
+        ),
+        view_settings: SettingsView::new(
+            font_list_repo,
+            font_file_repo,
+            prefs_repo,
+            sample_text_repo,
+            export_dir,
+        ),
         view_toolbar: Toolbar::new(),
         theme: Theme::Light,
     };
@@ -79,8 +98,7 @@ impl AppState {
         match message {
             AppCommand::Esc(id) => {
                 if self.expand_settings {
-                    self.expand_settings = false;
-                    Task::none()
+                    Task::done(AppCommand::XMessage(XMessage::SettingsClose))
                 } else {
                     Task::done(AppCommand::XMessage(XMessage::CloseRequested(id)))
                 }
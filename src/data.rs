@@ -14,5 +14,8 @@
  * limitations under the License.
  */
 
+pub mod clipboard;
+pub mod font_file;
 pub mod font_list;
 pub mod preferences;
+pub mod sample_text;
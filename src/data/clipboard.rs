@@ -0,0 +1,57 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::model::PreviewImage;
+use crate::prelude::*;
+
+#[cfg(test)]
+mod fake_clipboard;
+
+mod clipboard_impl;
+
+/// The clipboard API of iced only handles text, so images go through this data source.
+trait ClipboardDataSource {
+    fn write_image(&self, image: &PreviewImage) -> Fallible<()>;
+}
+
+pub struct ClipboardRepository {
+    #[cfg(not(test))]
+    data_source: clipboard_impl::ClipboardDataSourceImpl,
+
+    #[cfg(test)]
+    data_source: fake_clipboard::FakeClipboardDataSource,
+}
+
+impl ClipboardRepository {
+    pub fn write_image(&self, image: &PreviewImage) -> Fallible<()> {
+        self.data_source.write_image(image)
+    }
+}
+
+impl Default for ClipboardRepository {
+    #[cfg(not(test))]
+    fn default() -> Self {
+        Self {
+            data_source: clipboard_impl::ClipboardDataSourceImpl::new(),
+        }
+    }
+
+    #[cfg(test)]
+    fn default() -> Self {
+        Self {
+            data_source: fake_clipboard::FakeClipboardDataSource::new(),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::data::clipboard::ClipboardDataSource;
+use crate::model::PreviewImage;
+use crate::prelude::*;
+use arboard::{Clipboard, ImageData};
+use std::borrow::Cow;
+use std::sync::Mutex;
+
+#[cfg_attr(test, allow(unused))]
+pub struct ClipboardDataSourceImpl {
+    /// On X11 and Wayland the contents are served by the process that owns the clipboard, so the
+    /// handle is kept alive instead of being dropped after each write.
+    clipboard: Mutex<Option<Clipboard>>,
+}
+
+#[cfg_attr(test, allow(unused))]
+impl ClipboardDataSourceImpl {
+    pub fn new() -> Self {
+        Self {
+            clipboard: Mutex::new(None),
+        }
+    }
+}
+
+impl ClipboardDataSource for ClipboardDataSourceImpl {
+    fn write_image(&self, image: &PreviewImage) -> Fallible<()> {
+        let mut clipboard = self
+            .clipboard
+            .lock()
+            .map_err(|_| anyhow::anyhow!("clipboard lock is poisoned"))?;
+        let clipboard = match clipboard.as_mut() {
+            Some(data) => data,
+            None => clipboard.insert(Clipboard::new().context("failed to open clipboard")?),
+        };
+        clipboard
+            .set_image(ImageData {
+                width: image.width as usize,
+                height: image.height as usize,
+                bytes: Cow::Borrowed(&image.rgba),
+            })
+            .context("failed to write image to clipboard")
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::data::clipboard::ClipboardDataSource;
+use crate::model::PreviewImage;
+use crate::prelude::*;
+
+pub struct FakeClipboardDataSource;
+
+impl FakeClipboardDataSource {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ClipboardDataSource for FakeClipboardDataSource {
+    fn write_image(&self, _image: &PreviewImage) -> Fallible<()> {
+        Ok(())
+    }
+}
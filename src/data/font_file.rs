@@ -0,0 +1,70 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::model::FontFile;
+
+#[cfg(test)]
+mod fake_font_file;
+
+mod font_file_impl;
+
+trait FontFileDataSource {
+    fn find_by_name(&self, font_name: &str) -> Option<FontFile>;
+
+    /// `usWeightClass` of the font, e.g. 400 for regular and 700 for bold.
+    fn find_weight_class(&self, font_name: &str) -> Option<u16>;
+
+    /// Font used for labels in exported documents (e.g. display names that the
+    /// target font itself may not cover).
+    fn find_label_font(&self) -> Option<FontFile>;
+}
+
+pub struct FontFileRepository {
+    #[cfg(not(test))]
+    data_source: font_file_impl::FontFileDataSourceImpl,
+
+    #[cfg(test)]
+    data_source: fake_font_file::FakeFontFileDataSource,
+}
+
+impl FontFileRepository {
+    pub fn find_by_name(&self, font_name: &str) -> Option<FontFile> {
+        self.data_source.find_by_name(font_name)
+    }
+
+    pub fn find_weight_class(&self, font_name: &str) -> Option<u16> {
+        self.data_source.find_weight_class(font_name)
+    }
+
+    pub fn find_label_font(&self) -> Option<FontFile> {
+        self.data_source.find_label_font()
+    }
+}
+
+impl Default for FontFileRepository {
+    #[cfg(not(test))]
+    fn default() -> Self {
+        Self {
+            data_source: font_file_impl::FontFileDataSourceImpl::new(),
+        }
+    }
+
+    #[cfg(test)]
+    fn default() -> Self {
+        Self {
+            data_source: fake_font_file::FakeFontFileDataSource::new(),
+        }
+    }
+}
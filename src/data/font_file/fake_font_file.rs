@@ -0,0 +1,39 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::data::font_file::FontFileDataSource;
+use crate::model::FontFile;
+
+pub struct FakeFontFileDataSource;
+
+impl FakeFontFileDataSource {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl FontFileDataSource for FakeFontFileDataSource {
+    fn find_by_name(&self, _font_name: &str) -> Option<FontFile> {
+        None
+    }
+
+    fn find_weight_class(&self, _font_name: &str) -> Option<u16> {
+        None
+    }
+
+    fn find_label_font(&self) -> Option<FontFile> {
+        None
+    }
+}
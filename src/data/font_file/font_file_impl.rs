@@ -0,0 +1,92 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::data::font_file::FontFileDataSource;
+use crate::model::FontFile;
+use crate::prelude::*;
+use fontdb::{Database, Family, Query};
+use std::sync::OnceLock;
+
+/// Candidates for [`FontFileDataSource::find_label_font`], in order of preference. Japanese UI
+/// fonts come first so that display names such as `UビューE` can be rendered.
+const LABEL_FONT_FAMILIES: &[&str] = &[
+    "Yu Gothic UI",
+    "Yu Gothic",
+    "Meiryo",
+    "Hiragino Sans",
+    "Hiragino Kaku Gothic ProN",
+    "Noto Sans CJK JP",
+    "Noto Sans JP",
+];
+
+/// Resolves font files from the fonts installed on the system, which is where iced looks up
+/// `Font::with_name` as well.
+#[cfg_attr(test, allow(unused))]
+pub struct FontFileDataSourceImpl {
+    db: OnceLock<Database>,
+}
+
+#[cfg_attr(test, allow(unused))]
+impl FontFileDataSourceImpl {
+    pub fn new() -> Self {
+        Self {
+            db: OnceLock::new(),
+        }
+    }
+
+    /// Scanning the system fonts takes a while, so it is deferred until a font file is actually
+    /// needed.
+    fn db(&self) -> &Database {
+        self.db.get_or_init(|| {
+            let mut db = Database::new();
+            db.load_system_fonts();
+            debug!(faces = db.len(), "loaded system fonts");
+            db
+        })
+    }
+
+    fn query(&self, families: &[Family]) -> Option<FontFile> {
+        let db = self.db();
+        let id = db.query(&Query {
+            families,
+            ..Default::default()
+        })?;
+        db.with_face_data(id, |data, index| FontFile::new(data.to_vec(), index))
+    }
+}
+
+impl FontFileDataSource for FontFileDataSourceImpl {
+    fn find_by_name(&self, font_name: &str) -> Option<FontFile> {
+        self.query(&[Family::Name(font_name)])
+    }
+
+    fn find_weight_class(&self, font_name: &str) -> Option<u16> {
+        let db = self.db();
+        let id = db.query(&Query {
+            families: &[Family::Name(font_name)],
+            ..Default::default()
+        })?;
+        db.face(id).map(|face| face.weight.0)
+    }
+
+    fn find_label_font(&self) -> Option<FontFile> {
+        let mut families = LABEL_FONT_FAMILIES
+            .iter()
+            .map(|name| Family::Name(name))
+            .collect::<Vec<_>>();
+        families.push(Family::SansSerif);
+        self.query(&families)
+    }
+}
@@ -13,15 +13,43 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use crate::model::FontEntry;
+use crate::data::font_list::font_cache::FontCache;
+use crate::model::{
+    DEFAULT_FONT_CACHE_BUDGET_MB, FontCacheStatus, FontCatalog, FontEntry, FontFile,
+};
+use directories::ProjectDirs;
+use indexmap::IndexMap;
+use std::sync::{Mutex, MutexGuard};
 
 #[cfg(test)]
 mod fake_font_list;
 
+mod font_cache;
 mod font_list_impl;
+mod font_name_registry;
+
+pub use font_list_impl::legacy_font_ids;
+pub use font_name_registry::intern_font_name;
 
 trait FontListDataSource {
     fn find_all(&self) -> Vec<FontEntry>;
+
+    /// Reads the catalog again, e.g. after fonts have been added to the pack.
+    fn reload(&self) -> FontCatalog;
+
+    /// Version of the catalog, increased by releases that add fonts.
+    fn catalog_version(&self) -> u32;
+
+    /// IDs of the fonts added to the catalog after `version`.
+    fn find_ids_added_since(&self, version: u32) -> Vec<String>;
+
+    /// Fingerprint of the files in the pack directory, including the catalog override. `None`
+    /// if no pack directory is configured.
+    fn pack_signature(&self) -> Option<u64>;
+
+    /// Reads the font file of the entry from the pack. `None` if the pack is not available, in
+    /// which case the font is looked up from the installed fonts by name.
+    fn read_font_file(&self, font_entry: &FontEntry) -> Option<FontFile>;
 }
 
 pub struct FontListRepository {
@@ -30,26 +58,156 @@ pub struct FontListRepository {
 
     #[cfg(test)]
     data_source: fake_font_list::FakeFontListDataSource,
+
+    font_cache: Mutex<FontCache>,
+
+    /// Bytes per font name handed to iced, which keeps them until the app quits.
+    registered_fonts: Mutex<IndexMap<&'static str, usize>>,
 }
 
 impl FontListRepository {
+    #[cfg_attr(test, allow(unused_variables))]
+    pub fn new(project: &ProjectDirs) -> Self {
+        #[cfg(not(test))]
+        return Self {
+            data_source: font_list_impl::FontListDataSourceImpl::new(
+                project.data_dir().join("pack"),
+            ),
+            font_cache: default_font_cache(),
+            registered_fonts: Mutex::new(IndexMap::new()),
+        };
+
+        #[cfg(test)]
+        return Self::default();
+    }
+
     pub fn find_all(&self) -> Vec<FontEntry> {
         self.data_source.find_all()
     }
-}
 
-impl Default for FontListRepository {
-    #[cfg(not(test))]
-    fn default() -> Self {
-        Self {
-            data_source: font_list_impl::FontListDataSourceImpl::new(),
+    /// Reads the catalog again and drops the cached files of the fonts that have been moved or
+    /// replaced.
+    pub fn reload(&self) -> FontCatalog {
+        let catalog = self.data_source.reload();
+        let mut font_cache = self.font_cache();
+        for font_name in &catalog.changed_font_names {
+            font_cache.remove(font_name);
         }
+        catalog
     }
 
-    #[cfg(test)]
+    pub fn catalog_version(&self) -> u32 {
+        self.data_source.catalog_version()
+    }
+
+    pub fn find_ids_added_since(&self, version: u32) -> Vec<String> {
+        self.data_source.find_ids_added_since(version)
+    }
+
+    pub fn pack_signature(&self) -> Option<u64> {
+        self.data_source.pack_signature()
+    }
+
+    /// Font file of the entry, read from the pack on the first request and cached until it is
+    /// evicted by more recently used fonts.
+    pub fn load_font(&self, font_entry: &FontEntry) -> Option<FontFile> {
+        if let Some(file) = self.font_cache().get(font_entry.font_name) {
+            return Some(file);
+        }
+
+        // Read without holding the lock so that cached fonts can be served in the meantime.
+        let file = self.data_source.read_font_file(font_entry)?;
+        self.font_cache().insert(font_entry.font_name, file.clone());
+        Some(file)
+    }
+
+    /// Reads the font file of the entry without caching it, for scans over the whole catalog
+    /// that would otherwise evict the fonts on screen.
+    pub fn read_font_file(&self, font_entry: &FontEntry) -> Option<FontFile> {
+        self.data_source.read_font_file(font_entry)
+    }
+
+    pub fn set_font_cache_budget(&self, budget_bytes: usize) {
+        self.font_cache().set_budget(budget_bytes);
+    }
+
+    /// Records a font file registered with iced. Registering a font again, e.g. after its file
+    /// has been replaced, adds another copy.
+    pub fn record_registered_font(&self, font_name: &'static str, bytes: usize) {
+        *self.registered_fonts().entry(font_name).or_default() += bytes;
+    }
+
+    pub fn font_cache_status(&self) -> FontCacheStatus {
+        let mut status = self.font_cache().status();
+        status.registered_fonts = self
+            .registered_fonts()
+            .iter()
+            .map(|(name, bytes)| (*name, *bytes))
+            .collect();
+        status
+    }
+
+    fn font_cache(&self) -> MutexGuard<'_, FontCache> {
+        // The cache stays consistent even if a holder panicked, so a poisoned lock is reused.
+        self.font_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn registered_fonts(&self) -> MutexGuard<'_, IndexMap<&'static str, usize>> {
+        self.registered_fonts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn default_font_cache() -> Mutex<FontCache> {
+    Mutex::new(FontCache::new(
+        DEFAULT_FONT_CACHE_BUDGET_MB as usize * 1024 * 1024,
+    ))
+}
+
+#[cfg(test)]
+impl Default for FontListRepository {
     fn default() -> Self {
         Self {
             data_source: fake_font_list::FakeFontListDataSource::new(),
+            font_cache: default_font_cache(),
+            registered_fonts: Mutex::new(IndexMap::new()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_font_caches_file() {
+        let repo = FontListRepository::default();
+        let font_entries = repo.find_all();
+
+        let file = repo.load_font(&font_entries[0]).unwrap();
+
+        let status = repo.font_cache_status();
+        assert_eq!(
+            status.fonts,
+            vec![(font_entries[0].font_name, file.data.len())]
+        );
+        assert_eq!(status.used_bytes, file.data.len());
+        assert!(status.registered_fonts.is_empty());
+    }
+
+    #[test]
+    fn record_registered_font_adds_up_copies() {
+        let repo = FontListRepository::default();
+
+        repo.record_registered_font("a", 100);
+        repo.record_registered_font("b", 200);
+        repo.record_registered_font("a", 100);
+
+        let status = repo.font_cache_status();
+        assert_eq!(status.registered_fonts, vec![("a", 200), ("b", 200)]);
+        assert!(status.fonts.is_empty());
+    }
+}
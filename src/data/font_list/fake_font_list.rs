@@ -14,7 +14,7 @@
  * limitations under the License.
  */
 use crate::data::font_list::FontListDataSource;
-use crate::model::FontEntry;
+use crate::model::{FontCatalog, FontEntry, FontFile};
 
 pub struct FakeFontListDataSource {
     list: Vec<FontEntry>,
@@ -22,13 +22,15 @@ pub struct FakeFontListDataSource {
 
 impl FakeFontListDataSource {
     pub fn new() -> Self {
-        let mut list = vec![];
-        list.push(FontEntry::new("./arial.ttf".to_string(), None, "Arial"));
-        list.push(FontEntry::new(
-            "./times.ttf".to_string(),
-            None,
-            "Times New Roman",
-        ));
+        let list = vec![
+            FontEntry::new("Arial".into(), "./arial.ttf".into(), None, "Arial"),
+            FontEntry::new(
+                "Times New Roman".into(),
+                "./times.ttf".into(),
+                None,
+                "Times New Roman",
+            ),
+        ];
 
         Self { list }
     }
@@ -38,4 +40,35 @@ impl FontListDataSource for FakeFontListDataSource {
     fn find_all(&self) -> Vec<FontEntry> {
         self.list.clone()
     }
+
+    fn reload(&self) -> FontCatalog {
+        FontCatalog {
+            entries: self.list.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Version 2 added "Times New Roman".
+    fn catalog_version(&self) -> u32 {
+        2
+    }
+
+    fn find_ids_added_since(&self, version: u32) -> Vec<String> {
+        if version < 2 {
+            vec!["Times New Roman".to_string()]
+        } else {
+            vec![]
+        }
+    }
+
+    fn pack_signature(&self) -> Option<u64> {
+        None
+    }
+
+    fn read_font_file(&self, font_entry: &FontEntry) -> Option<FontFile> {
+        self.list
+            .iter()
+            .any(|entry| entry.filepath == font_entry.filepath)
+            .then(|| FontFile::new(font_entry.filepath.clone().into_bytes(), 0))
+    }
 }
@@ -0,0 +1,129 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::model::{FontCacheStatus, FontFile};
+use crate::prelude::*;
+use indexmap::IndexMap;
+
+/// Font files read from the pack, evicted in least recently used order once their total size
+/// exceeds the budget.
+///
+/// The budget only bounds this cache, which saves reading the files again. It does not bound the
+/// memory of the app since iced keeps a copy of every registered font until the app quits.
+pub struct FontCache {
+    budget_bytes: usize,
+    used_bytes: usize,
+    /// Ordered from the least to the most recently used.
+    fonts: IndexMap<&'static str, FontFile>,
+}
+
+impl FontCache {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            budget_bytes,
+            used_bytes: 0,
+            fonts: IndexMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, font_name: &str) -> Option<FontFile> {
+        let index = self.fonts.get_index_of(font_name)?;
+        let last = self.fonts.len() - 1;
+        self.fonts.move_index(index, last);
+        self.fonts.get_index(last).map(|(_, file)| file.clone())
+    }
+
+    pub fn insert(&mut self, font_name: &'static str, file: FontFile) {
+        if let Some(old) = self.fonts.shift_remove(font_name) {
+            self.used_bytes -= old.data.len();
+        }
+        self.used_bytes += file.data.len();
+        self.fonts.insert(font_name, file);
+        self.evict();
+    }
+
+    pub fn remove(&mut self, font_name: &str) {
+        if let Some(file) = self.fonts.shift_remove(font_name) {
+            self.used_bytes -= file.data.len();
+        }
+    }
+
+    pub fn set_budget(&mut self, budget_bytes: usize) {
+        self.budget_bytes = budget_bytes;
+        self.evict();
+    }
+
+    pub fn status(&self) -> FontCacheStatus {
+        FontCacheStatus {
+            budget_bytes: self.budget_bytes,
+            used_bytes: self.used_bytes,
+            fonts: self
+                .fonts
+                .iter()
+                .map(|(name, file)| (*name, file.data.len()))
+                .collect(),
+            registered_fonts: vec![],
+        }
+    }
+
+    /// Drops the least recently used fonts until the cache fits in the budget. The most recently
+    /// used font is kept even if it alone exceeds the budget, since it has just been requested.
+    fn evict(&mut self) {
+        while self.used_bytes > self.budget_bytes && self.fonts.len() > 1 {
+            let Some((font_name, file)) = self.fonts.shift_remove_index(0) else {
+                break;
+            };
+            self.used_bytes -= file.data.len();
+            debug!(font_name, bytes = file.data.len(), "evicted font");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font_file(len: usize) -> FontFile {
+        FontFile::new(vec![0; len], 0)
+    }
+
+    #[test]
+    fn insert_evicts_least_recently_used() {
+        let mut cache = FontCache::new(300);
+        cache.insert("a", font_file(100));
+        cache.insert("b", font_file(100));
+        cache.insert("c", font_file(100));
+        assert!(cache.get("a").is_some());
+
+        cache.insert("d", font_file(100));
+
+        let status = cache.status();
+        assert_eq!(status.used_bytes, 300);
+        assert_eq!(status.fonts, vec![("c", 100), ("a", 100), ("d", 100)]);
+    }
+
+    #[test]
+    fn set_budget_keeps_most_recently_used() {
+        let mut cache = FontCache::new(300);
+        cache.insert("a", font_file(100));
+        cache.insert("b", font_file(200));
+
+        cache.set_budget(50);
+
+        let status = cache.status();
+        assert_eq!(status.used_bytes, 200);
+        assert_eq!(status.fonts, vec![("b", 200)]);
+    }
+}
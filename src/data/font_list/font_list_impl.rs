@@ -14,55 +14,413 @@
  * limitations under the License.
  */
 use crate::asset::Asset;
-use crate::data::font_list::FontListDataSource;
-use crate::model::FontEntry;
+use crate::data::font_list::{FontListDataSource, intern_font_name};
+use crate::model::{FontCatalog, FontEntry, FontFile};
+use crate::prelude::*;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::str::from_utf8;
+use std::sync::{Mutex, RwLock};
+use std::time::SystemTime;
+
+/// Environment variable pointing to the extracted Fonts66 pack, overriding the `pack` folder in
+/// the data directory of the app. The `filepath` of the catalog entries is relative to this
+/// directory.
+const PACK_DIR_ENV: &str = "FONTS66_PACK_DIR";
+
+/// Catalog in the pack directory that replaces the embedded `font_list.toml`, e.g. for a newer
+/// pack release.
+const CATALOG_OVERRIDE_FILENAME: &str = "font_list.toml";
+
+const FONT_FILE_EXTENSIONS: &[&str] = &["otf", "ttc", "ttf"];
 
 #[derive(Default)]
 pub struct FontListDataSourceImpl {
-    cache: Vec<FontEntry>,
+    cache: RwLock<Catalog>,
+    pack_dir: Option<PathBuf>,
+    family_names: Mutex<FamilyNames>,
+}
+
+#[derive(Clone, Default)]
+struct Catalog {
+    version: u32,
+    entries: Vec<FontEntry>,
+    /// Catalog version that added each font, by ID.
+    since: HashMap<String, u32>,
+    /// Stamps of the font files in the pack, by `filepath`.
+    stamps: HashMap<String, FileStamp>,
+}
+
+/// Size and modification time of a file, which change when the file is replaced.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = path.metadata().ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
 }
 
+/// Family names of the uncatalogued font files, with the stamp of the file they were read from.
+type FamilyNames = HashMap<PathBuf, (FileStamp, Option<String>)>;
+
 impl FontListDataSourceImpl {
-    pub fn new() -> Self {
-        let font_list = Asset::get("font_list.toml").expect("font_list.toml is not found");
-        let config_string = from_utf8(&font_list.data).expect("font_list.toml is not valid utf-8");
+    pub fn new(default_pack_dir: PathBuf) -> Self {
+        let pack_dir = std::env::var_os(PACK_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or(default_pack_dir);
+        debug!(?pack_dir, "font pack");
 
-        let cache = toml::from_str::<FontListConfig>(config_string)
-            .expect("font_list.toml is invalid")
-            .entry
-            .into_iter()
-            .map(|dto| {
-                FontEntry::new(
-                    dto.filepath,
-                    dto.display_name,
-                    Box::leak(dto.name.into_boxed_str()),
-                )
-            })
-            .collect();
+        let mut family_names = FamilyNames::new();
+        Self {
+            cache: RwLock::new(load_catalog(Some(&pack_dir), &mut family_names)),
+            pack_dir: Some(pack_dir),
+            family_names: Mutex::new(family_names),
+        }
+    }
+}
 
-        Self { cache }
+impl FontListDataSourceImpl {
+    fn catalog(&self) -> std::sync::RwLockReadGuard<'_, Catalog> {
+        self.cache
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
     }
 }
 
 impl FontListDataSource for FontListDataSourceImpl {
     fn find_all(&self) -> Vec<FontEntry> {
-        self.cache.clone()
+        self.catalog().entries.clone()
+    }
+
+    fn reload(&self) -> FontCatalog {
+        let catalog = load_catalog(
+            self.pack_dir.as_deref(),
+            &mut self
+                .family_names
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        let mut cache = self
+            .cache
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let changed_font_names = find_changed_font_names(&cache, &catalog);
+        let entries = catalog.entries.clone();
+        *cache = catalog;
+        FontCatalog {
+            entries,
+            changed_font_names,
+        }
+    }
+
+    fn catalog_version(&self) -> u32 {
+        self.catalog().version
+    }
+
+    fn find_ids_added_since(&self, version: u32) -> Vec<String> {
+        let catalog = self.catalog();
+        catalog
+            .entries
+            .iter()
+            .filter(|entry| {
+                catalog
+                    .since
+                    .get(&entry.id)
+                    .is_some_and(|since| *since > version)
+            })
+            .map(|entry| entry.id.clone())
+            .collect()
+    }
+
+    fn pack_signature(&self) -> Option<u64> {
+        let pack_dir = self.pack_dir.as_ref()?;
+        let mut hasher = DefaultHasher::new();
+        for path in find_files(pack_dir) {
+            path.hash(&mut hasher);
+            FileStamp::read(&path).hash(&mut hasher);
+        }
+        Some(hasher.finish())
+    }
+
+    fn read_font_file(&self, font_entry: &FontEntry) -> Option<FontFile> {
+        let path = self.pack_dir.as_ref()?.join(&font_entry.filepath);
+        match std::fs::read(&path) {
+            Ok(data) => {
+                debug!(?path, bytes = data.len(), "read font file");
+                let index = find_face_index(&data, font_entry.font_name);
+                Some(FontFile::new(data, index))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => {
+                warn!(?e, ?path, "failed to read font file");
+                None
+            }
+        }
+    }
+}
+
+/// Reads the catalog override of the pack, or the embedded catalog, followed by the font files
+/// of the pack that the catalog doesn't list.
+fn load_catalog(pack_dir: Option<&Path>, family_names: &mut FamilyNames) -> Catalog {
+    let config = pack_dir
+        .map(|dir| dir.join(CATALOG_OVERRIDE_FILENAME))
+        .filter(|path| path.exists())
+        .and_then(|path| {
+            let config = std::fs::read_to_string(&path)
+                .context("failed to read the catalog")
+                .and_then(|data| Ok(toml::from_str::<FontListConfig>(&data)?));
+            match config {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    warn!(?e, ?path, "failed to load the catalog override");
+                    None
+                }
+            }
+        })
+        .unwrap_or_else(|| {
+            let font_list = Asset::get("font_list.toml").expect("font_list.toml is not found");
+            let config_string =
+                from_utf8(&font_list.data).expect("font_list.toml is not valid utf-8");
+            toml::from_str::<FontListConfig>(config_string).expect("font_list.toml is invalid")
+        });
+
+    let mut since = HashMap::new();
+    let mut stamps = HashMap::new();
+    let mut entries = config
+        .entry
+        .into_iter()
+        .map(|dto| {
+            let id = dto.id.unwrap_or_else(|| dto.name.clone());
+            since.insert(id.clone(), dto.since);
+            FontEntry::new(
+                id,
+                dto.filepath,
+                dto.display_name,
+                intern_font_name(&dto.name),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(pack_dir) = pack_dir {
+        let filepaths = entries
+            .iter()
+            .map(|entry| entry.filepath.clone())
+            .collect::<HashSet<_>>();
+        let mut ids = entries
+            .iter()
+            .map(|entry| entry.id.clone())
+            .collect::<HashSet<_>>();
+        for path in find_files(pack_dir) {
+            let is_font_file = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| FONT_FILE_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+            if !is_font_file {
+                continue;
+            }
+            let (Some(filepath), Some(stamp)) =
+                (pack_filepath(pack_dir, &path), FileStamp::read(&path))
+            else {
+                continue;
+            };
+            stamps.insert(filepath.clone(), stamp);
+            if filepaths.contains(&filepath) {
+                continue;
+            }
+            // Uncatalogued fonts are identified by their family name, like the catalog does by
+            // default. A font the catalog lists under another path is not added twice.
+            match cached_family_name(&path, stamp, family_names) {
+                Some(family_name) if ids.insert(family_name.clone()) => {
+                    entries.push(FontEntry::new(
+                        family_name.clone(),
+                        filepath,
+                        None,
+                        intern_font_name(&family_name),
+                    ))
+                }
+                Some(_) => {}
+                None => warn!(?path, "font file without a family name"),
+            }
+        }
+    }
+
+    debug!(
+        version = config.version,
+        entries = entries.len(),
+        "loaded catalog"
+    );
+    Catalog {
+        version: config.version,
+        entries,
+        since,
+        stamps,
+    }
+}
+
+/// Fonts of `new` whose file differs from the one in `old` by path or stamp.
+fn find_changed_font_names(old: &Catalog, new: &Catalog) -> HashSet<&'static str> {
+    let old_files = old
+        .entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.font_name,
+                (&entry.filepath, old.stamps.get(&entry.filepath)),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    new.entries
+        .iter()
+        .filter(|entry| {
+            old_files.get(entry.font_name).is_some_and(|old_file| {
+                *old_file != (&entry.filepath, new.stamps.get(&entry.filepath))
+            })
+        })
+        .map(|entry| entry.font_name)
+        .collect()
+}
+
+/// IDs of the `filepath`s that presets stored before 0.3.0, from the embedded catalog and
+/// `font_id_remap.toml` for paths that are no longer in the catalog.
+pub fn legacy_font_ids() -> HashMap<String, String> {
+    let font_list = Asset::get("font_list.toml").expect("font_list.toml is not found");
+    let config_string = from_utf8(&font_list.data).expect("font_list.toml is not valid utf-8");
+    let mut font_ids = toml::from_str::<FontListConfig>(config_string)
+        .expect("font_list.toml is invalid")
+        .entry
+        .into_iter()
+        .map(|dto| (dto.filepath, dto.id.unwrap_or(dto.name)))
+        .collect::<HashMap<_, _>>();
+
+    let remap = Asset::get("font_id_remap.toml").expect("font_id_remap.toml is not found");
+    let remap_string = from_utf8(&remap.data).expect("font_id_remap.toml is not valid utf-8");
+    font_ids.extend(
+        toml::from_str::<FontIdRemapConfig>(remap_string)
+            .expect("font_id_remap.toml is invalid")
+            .entry
+            .into_iter()
+            .map(|dto| (dto.filepath, dto.id)),
+    );
+    font_ids
+}
+
+/// Files under `dir` in a stable order.
+fn find_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let read_dir = match std::fs::read_dir(&dir) {
+            Ok(data) => data,
+            Err(e) => {
+                if e.kind() != ErrorKind::NotFound {
+                    warn!(?e, ?dir, "failed to read the pack directory");
+                }
+                continue;
+            }
+        };
+        for entry in read_dir.flatten() {
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => dirs.push(entry.path()),
+                Ok(_) => files.push(entry.path()),
+                Err(_) => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Path in the style of the catalog, e.g. `./桜/02_uヴュー(12)/UビューE.ttf`.
+fn pack_filepath(pack_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(pack_dir).ok()?;
+    let components = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(format!("./{}", components.join("/")))
+}
+
+/// Family name of the font file, read again only if the file has changed since the last read.
+fn cached_family_name(
+    path: &Path,
+    stamp: FileStamp,
+    family_names: &mut FamilyNames,
+) -> Option<String> {
+    if let Some((cached_stamp, family_name)) = family_names.get(path)
+        && *cached_stamp == stamp
+    {
+        return family_name.clone();
+    }
+    let family_name = read_family_name(path);
+    family_names.insert(path.to_path_buf(), (stamp, family_name.clone()));
+    family_name
+}
+
+fn read_family_name(path: &Path) -> Option<String> {
+    let mut db = fontdb::Database::new();
+    db.load_font_file(path).ok()?;
+    let face = db.faces().next()?;
+    face.families.first().map(|(name, _)| name.clone())
+}
+
+/// Index of the face named `font_name` in a font collection, like iced picks it by family name.
+/// Faces of other files, and collections without such a face, are at 0.
+fn find_face_index(data: &[u8], font_name: &str) -> u32 {
+    if data.get(..4) != Some(b"ttcf") {
+        return 0;
     }
+    let mut db = fontdb::Database::new();
+    db.load_font_data(data.to_vec());
+    db.faces()
+        .find(|face| face.families.iter().any(|(name, _)| name == font_name))
+        .map_or(0, |face| face.index)
 }
 
 #[derive(Deserialize)]
 struct FontListConfig {
+    #[serde(default = "default_catalog_version")]
+    version: u32,
     entry: Vec<FontEntryDTO>,
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "kebab-case")]
 struct FontEntryDTO {
+    /// Defaults to `name`, which stays the same when a pack release renames a folder.
+    id: Option<String>,
     filepath: String,
     display_name: Option<String>,
     name: String,
+    /// Catalog version that added the entry.
+    #[serde(default = "default_catalog_version")]
+    since: u32,
+}
+
+fn default_catalog_version() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+struct FontIdRemapConfig {
+    #[serde(default)]
+    entry: Vec<FontIdRemapDTO>,
+}
+
+#[derive(Deserialize)]
+struct FontIdRemapDTO {
+    filepath: String,
+    id: String,
 }
 
 #[cfg(test)]
@@ -73,7 +431,189 @@ mod tests {
 
     #[test]
     fn test_find_all() {
-        let data_source = FontListDataSourceImpl::new();
+        let dir = tempfile::tempdir().unwrap();
+        let data_source = FontListDataSourceImpl::new(dir.path().join("pack"));
         let _ = data_source.find_all();
     }
+
+    #[test]
+    fn load_catalog_reads_override_and_signature_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_source = FontListDataSourceImpl {
+            cache: RwLock::new(Catalog::default()),
+            pack_dir: Some(dir.path().to_path_buf()),
+            family_names: Default::default(),
+        };
+        let signature = data_source.pack_signature();
+
+        std::fs::create_dir(dir.path().join("桜")).unwrap();
+        std::fs::write(
+            dir.path().join(CATALOG_OVERRIDE_FILENAME),
+            "version = 2\n\n[[entry]]\nfilepath = \"./桜/new.ttf\"\nname = \"NewFont\"\nsince = 2\n",
+        )
+        .unwrap();
+        // Not a font file, so it's only part of the signature.
+        std::fs::write(dir.path().join("桜").join("readme.txt"), "").unwrap();
+
+        assert_ne!(data_source.pack_signature(), signature);
+        let entries = data_source.reload().entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, "NewFont");
+        assert_eq!(entries[0].filepath, "./桜/new.ttf");
+        assert_eq!(entries[0].font_name, "NewFont");
+        assert_eq!(data_source.find_all().len(), 1);
+        assert_eq!(data_source.catalog_version(), 2);
+        assert_eq!(data_source.find_ids_added_since(1), vec!["NewFont"]);
+        assert!(data_source.find_ids_added_since(2).is_empty());
+    }
+
+    #[test]
+    fn find_changed_font_names_compares_path_and_stamp() {
+        let stamp = FileStamp {
+            len: 100,
+            modified: None,
+        };
+        let entries = ["a", "b", "c"]
+            .map(|name| FontEntry::new(name.into(), format!("./{name}.ttf"), None, name))
+            .to_vec();
+        let old = Catalog {
+            stamps: entries
+                .iter()
+                .map(|entry| (entry.filepath.clone(), stamp))
+                .collect(),
+            entries: entries.clone(),
+            ..Default::default()
+        };
+        let mut new = old.clone();
+        new.entries[1] = FontEntry::new("b".into(), "./moved/b.ttf".into(), None, "b");
+        new.stamps
+            .insert("./moved/b.ttf".into(), FileStamp { len: 100, ..stamp });
+        new.stamps
+            .insert("./c.ttf".into(), FileStamp { len: 200, ..stamp });
+        new.entries
+            .push(FontEntry::new("d".into(), "./d.ttf".into(), None, "d"));
+
+        assert_eq!(
+            find_changed_font_names(&old, &new),
+            HashSet::from(["b", "c"])
+        );
+    }
+
+    #[test]
+    fn cached_family_name_reads_again_only_if_file_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("font.ttf");
+        std::fs::write(&path, "not a font").unwrap();
+        let stamp = FileStamp::read(&path).unwrap();
+        let mut family_names =
+            FamilyNames::from([(path.clone(), (stamp, Some("Cached".to_string())))]);
+
+        assert_eq!(
+            cached_family_name(&path, stamp, &mut family_names).as_deref(),
+            Some("Cached"),
+        );
+
+        let changed = FileStamp {
+            len: stamp.len + 1,
+            ..stamp
+        };
+        assert_eq!(cached_family_name(&path, changed, &mut family_names), None);
+        assert_eq!(family_names[&path], (changed, None));
+    }
+
+    /// Collection of faces that only have a `name` table with the family and PostScript names.
+    fn named_collection(families: &[&str]) -> Vec<u8> {
+        let be16 = |values: &[u16]| {
+            values
+                .iter()
+                .flat_map(|v| v.to_be_bytes())
+                .collect::<Vec<_>>()
+        };
+        let names = families
+            .iter()
+            .map(|family| {
+                let string = family
+                    .encode_utf16()
+                    .flat_map(u16::to_be_bytes)
+                    .collect::<Vec<_>>();
+                let len = string.len() as u16;
+                let mut table = be16(&[0, 2, 6 + 12 * 2]);
+                for name_id in [1, 6] {
+                    table.extend(be16(&[3, 1, 0x409, name_id, len, 0]));
+                }
+                table.extend(string);
+                table
+            })
+            .collect::<Vec<_>>();
+
+        let header_len = 12 + 4 * families.len();
+        let directory_len = 12 + 16;
+        let mut data = b"ttcf".to_vec();
+        data.extend(0x00010000u32.to_be_bytes());
+        data.extend((families.len() as u32).to_be_bytes());
+        let mut directories = vec![];
+        let mut table_offset = header_len + directory_len * families.len();
+        for (i, name) in names.iter().enumerate() {
+            data.extend(((header_len + directory_len * i) as u32).to_be_bytes());
+            directories.extend(0x00010000u32.to_be_bytes());
+            directories.extend(be16(&[1, 0, 0, 0]));
+            directories.extend(b"name");
+            directories.extend(0u32.to_be_bytes());
+            directories.extend((table_offset as u32).to_be_bytes());
+            directories.extend((name.len() as u32).to_be_bytes());
+            table_offset += name.len();
+        }
+        data.extend(directories);
+        data.extend(names.concat());
+        data
+    }
+
+    #[test]
+    fn read_font_file_picks_the_face_of_a_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("fonts.ttc"),
+            named_collection(&["Regular", "Bold"]),
+        )
+        .unwrap();
+        let data_source = FontListDataSourceImpl {
+            cache: RwLock::new(Catalog::default()),
+            pack_dir: Some(dir.path().to_path_buf()),
+            family_names: Default::default(),
+        };
+        let entry = |font_name: &'static str| {
+            FontEntry::new(font_name.into(), "./fonts.ttc".into(), None, font_name)
+        };
+
+        assert_eq!(data_source.read_font_file(&entry("Bold")).unwrap().index, 1);
+        assert_eq!(
+            data_source.read_font_file(&entry("Regular")).unwrap().index,
+            0
+        );
+        assert_eq!(
+            data_source.read_font_file(&entry("Other")).unwrap().index,
+            0
+        );
+    }
+
+    #[test]
+    fn legacy_font_ids_maps_catalog_paths() {
+        let font_ids = legacy_font_ids();
+
+        assert_eq!(
+            font_ids
+                .get("./桜/02_uヴュー(12)/UビューE.ttf")
+                .map(String::as_str),
+            Some("uVeiwE"),
+        );
+    }
+
+    #[test]
+    fn pack_filepath_uses_catalog_style() {
+        let pack_dir = Path::new("pack");
+        assert_eq!(
+            pack_filepath(pack_dir, &pack_dir.join("桜").join("UビューE.ttf")).as_deref(),
+            Some("./桜/UビューE.ttf"),
+        );
+    }
 }
@@ -0,0 +1,53 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+/// Font names handed out by [`intern_font_name`]. Entries are never removed, so the memory is
+/// bounded by the number of distinct names rather than the number of catalog loads.
+static FONT_NAMES: LazyLock<Mutex<HashSet<&'static str>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Returns a `'static` handle for `name` as required by `Font::with_name`. The string is leaked
+/// on the first call for a name and shared by every later call, so catalogs can be rebuilt at
+/// runtime without leaking on each rebuild.
+pub fn intern_font_name(name: &str) -> &'static str {
+    // Names are only ever inserted, so a poisoned set is still consistent.
+    let mut font_names = FONT_NAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(interned) = font_names.get(name) {
+        return interned;
+    }
+
+    let interned: &'static str = Box::leak(name.to_owned().into_boxed_str());
+    font_names.insert(interned);
+    interned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_font_name_returns_same_handle() {
+        let first = intern_font_name(&String::from("uVeiwE"));
+        let second = intern_font_name(&String::from("uVeiwE"));
+
+        assert!(std::ptr::eq(first, second));
+        assert!(!std::ptr::eq(first, intern_font_name("uVeiwR")));
+    }
+}
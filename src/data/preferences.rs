@@ -13,19 +13,30 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use crate::model::Preferences;
+use crate::model::{Preferences, Preset};
 use crate::prelude::*;
 use directories::ProjectDirs;
+use std::path::Path;
 
 #[cfg(test)]
 mod fake_preferences;
 
 mod local_preferences;
+mod share_code;
+
+pub use share_code::{decode_share_code, encode_share_code};
 
 trait PreferencesDataSource {
+    /// Read-only presets shipped with the app.
+    fn find_builtin_presets(&self) -> Vec<Preset>;
+
     fn retrieve(&self) -> Fallible<Preferences>;
 
     fn save(&self, preferences: Preferences) -> Fallible<()>;
+
+    fn read_preset_file(&self, path: &Path) -> Fallible<Vec<Preset>>;
+
+    fn write_preset_file(&self, presets: Vec<Preset>, path: &Path) -> Fallible<()>;
 }
 
 pub struct PreferencesRepository {
@@ -50,6 +61,10 @@ impl PreferencesRepository {
         };
     }
 
+    pub fn find_builtin_presets(&self) -> Vec<Preset> {
+        self.data_source.find_builtin_presets()
+    }
+
     pub fn retrieve(&self) -> Fallible<Preferences> {
         self.data_source.retrieve()
     }
@@ -57,4 +72,17 @@ impl PreferencesRepository {
     pub fn save(&self, preferences: Preferences) -> Fallible<()> {
         self.data_source.save(preferences)
     }
+
+    /// Reads presets shared by [`Self::write_preset_file`], migrating files written by older
+    /// versions.
+    pub fn read_preset_file(&self, path: &Path) -> Fallible<Vec<Preset>> {
+        self.data_source.read_preset_file(path)
+    }
+
+    /// Writes `presets` to a standalone file in the [`PresetFileFormat`] of its extension.
+    ///
+    /// [`PresetFileFormat`]: crate::model::PresetFileFormat
+    pub fn write_preset_file(&self, presets: Vec<Preset>, path: &Path) -> Fallible<()> {
+        self.data_source.write_preset_file(presets, path)
+    }
 }
@@ -14,12 +14,15 @@
  * limitations under the License.
  */
 use crate::data::preferences::PreferencesDataSource;
-use crate::model::{Preferences, Preset};
+use crate::model::{DEFAULT_FONT_CACHE_BUDGET_MB, Preferences, Preset, SpecimenTemplate};
 use crate::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 pub struct FakePreferencesDataSource {
     preferences: Mutex<Preferences>,
+    preset_files: Mutex<HashMap<PathBuf, Vec<Preset>>>,
 }
 
 impl FakePreferencesDataSource {
@@ -27,18 +30,33 @@ impl FakePreferencesDataSource {
         let preferences = Preferences {
             presets: vec![Preset {
                 name: "Preset 1".to_string(),
-                font_size: 24,
-                enable_paths: vec!["./arial.ttf".to_string()],
+                template: SpecimenTemplate::with_headline_size(24),
+                enabled_ids: vec!["Arial".to_string()],
+                ..Default::default()
             }],
+            sample_text_history: vec![],
+            font_cache_budget_mb: DEFAULT_FONT_CACHE_BUDGET_MB,
+            catalog_version: None,
+            favourite_ids: vec![],
         };
 
         Self {
             preferences: Mutex::new(preferences),
+            preset_files: Mutex::new(HashMap::new()),
         }
     }
 }
 
 impl PreferencesDataSource for FakePreferencesDataSource {
+    fn find_builtin_presets(&self) -> Vec<Preset> {
+        vec![Preset {
+            name: "明朝".to_string(),
+            template: SpecimenTemplate::with_headline_size(32),
+            enabled_ids: vec!["Times New Roman".to_string()],
+            ..Default::default()
+        }]
+    }
+
     fn retrieve(&self) -> Fallible<Preferences> {
         let prefs = self.preferences.lock().unwrap();
         Ok(Preferences {
@@ -47,10 +65,21 @@ impl PreferencesDataSource for FakePreferencesDataSource {
                 .iter()
                 .map(|p| Preset {
                     name: p.name.clone(),
-                    font_size: p.font_size,
-                    enable_paths: p.enable_paths.clone(),
+                    template: p.template.clone(),
+                    enabled_ids: p.enabled_ids.clone(),
+                    custom_text: p.custom_text.clone(),
+                    sort_mode: p.sort_mode,
+                    layout: p.layout,
+                    font_selection_mode: p.font_selection_mode,
+                    disabled_ids: p.disabled_ids.clone(),
+                    rules: p.rules.clone(),
+                    font_order: p.font_order.clone(),
                 })
                 .collect(),
+            sample_text_history: prefs.sample_text_history.clone(),
+            font_cache_budget_mb: prefs.font_cache_budget_mb,
+            catalog_version: prefs.catalog_version,
+            favourite_ids: prefs.favourite_ids.clone(),
         })
     }
 
@@ -58,4 +87,21 @@ impl PreferencesDataSource for FakePreferencesDataSource {
         *self.preferences.lock().unwrap() = preferences;
         Ok(())
     }
+
+    fn read_preset_file(&self, path: &Path) -> Fallible<Vec<Preset>> {
+        self.preset_files
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .with_context(|| format!("failed to read {}", path.display()))
+    }
+
+    fn write_preset_file(&self, presets: Vec<Preset>, path: &Path) -> Fallible<()> {
+        self.preset_files
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), presets);
+        Ok(())
+    }
 }
@@ -13,17 +13,27 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::asset::Asset;
+use crate::data::font_list::legacy_font_ids;
 use crate::data::preferences::PreferencesDataSource;
-use crate::model::{Preferences, Preset, SQLiteUserVersion};
+use crate::model::{
+    DEFAULT_FONT_CACHE_BUDGET_MB, DEFAULT_SAMPLE_FONT_SIZE, FontRule, FontSelectionMode,
+    Preferences, Preset, PresetFileFormat, PreviewLayout, PreviewLayoutKind, SQLiteUserVersion,
+    SortMode, SpecimenRow, SpecimenRowKind, SpecimenTemplate,
+};
 use crate::prelude::*;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::io::prelude::*;
 use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::from_utf8;
 use std::sync::{Mutex, MutexGuard};
 
+/// Reads the user's presets from `preferences.toml` in the config dir, and the built-in presets
+/// from the embedded `builtin_presets.toml`.
 pub struct LocalPreferencesDataSource {
+    builtin_presets: Vec<Preset>,
     mutex: Mutex<()>,
     pathname: PathBuf,
 }
@@ -35,7 +45,17 @@ impl LocalPreferencesDataSource {
     }
 
     fn with_pathname(pathname: PathBuf) -> Self {
+        let asset = Asset::get("builtin_presets.toml").expect("builtin_presets.toml is not found");
+        let content = from_utf8(&asset.data).expect("builtin_presets.toml is not valid utf-8");
+        let builtin_presets = toml::from_str::<BuiltinPresetsDTO>(content)
+            .expect("builtin_presets.toml is invalid")
+            .entry
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
         Self {
+            builtin_presets,
             mutex: Mutex::new(()),
             pathname,
         }
@@ -69,8 +89,8 @@ impl LocalPreferencesDataSource {
     /// Migrate preferences from an older version to the current version.
     ///
     /// To add a new migration step, add an `if *_file_version < "x.y.z".parse()? { ... }` block
-    /// below. When adding new fields to DTOs, use `#[serde(default)]` to maintain compatibility
-    /// with older files.
+    /// below, or to [`migrate_presets`] if it only changes presets. When adding new fields to
+    /// DTOs, use `#[serde(default)]` to maintain compatibility with older files.
     ///
     /// If a future migration requires destructive changes that cannot be handled by
     /// `#[serde(default)]` (e.g., field type changes or structural reorganization), use
@@ -78,23 +98,53 @@ impl LocalPreferencesDataSource {
     fn migrate(
         &self,
         mut dto: PreferencesDTO,
-        _file_version: &SQLiteUserVersion,
+        file_version: &SQLiteUserVersion,
         current_version: &SQLiteUserVersion,
     ) -> Fallible<PreferencesDTO> {
-        info!(%_file_version, %current_version, "migrating preferences");
+        info!(%file_version, %current_version, "migrating preferences");
 
-        // if *_file_version < "0.2.0".parse()? {
-        //     for preset in &mut dto.presets {
-        //         // apply migration for 0.2.0
-        //     }
-        // }
+        migrate_presets(&mut dto.presets, file_version)?;
 
         dto.version = current_version.to_string();
         Ok(dto)
     }
 }
 
+/// Migration steps of [`PresetDTO`], shared by the preferences and the preset files.
+fn migrate_presets(presets: &mut [PresetDTO], file_version: &SQLiteUserVersion) -> Fallible<()> {
+    // 0.2.0 replaced the single font size of a preset with a specimen template.
+    if *file_version < "0.2.0".parse()? {
+        for preset in presets.iter_mut() {
+            let font_size = preset.font_size.take().unwrap_or(DEFAULT_SAMPLE_FONT_SIZE);
+            preset
+                .template
+                .get_or_insert_with(|| SpecimenTemplate::with_headline_size(font_size).into());
+        }
+    }
+
+    // 0.3.0 refers to fonts by ID instead of their path in the pack. Unknown paths are kept
+    // as they are so that they still show up as missing fonts.
+    if *file_version < "0.3.0".parse()? {
+        let font_ids = legacy_font_ids();
+        for preset in presets.iter_mut() {
+            let Some(paths) = preset.enable_paths.take() else {
+                continue;
+            };
+            preset.enabled_ids = paths
+                .into_iter()
+                .map(|path| font_ids.get(&path).cloned().unwrap_or(path))
+                .collect();
+        }
+    }
+
+    Ok(())
+}
+
 impl PreferencesDataSource for LocalPreferencesDataSource {
+    fn find_builtin_presets(&self) -> Vec<Preset> {
+        self.builtin_presets.clone()
+    }
+
     /// Retrieve preferences from the TOML file.
     ///
     /// Migration is lazily executed during retrieval (no explicit migration command at app startup).
@@ -104,7 +154,7 @@ impl PreferencesDataSource for LocalPreferencesDataSource {
         let _guard = self.lock();
 
         if !self.pathname.exists() {
-            return Ok(Preferences { presets: vec![] });
+            return Ok(Preferences::default());
         }
 
         let mut content = String::new();
@@ -130,25 +180,165 @@ impl PreferencesDataSource for LocalPreferencesDataSource {
         let dto = PreferencesDTO::from(preferences);
         self.write_dto(&dto)
     }
+
+    fn read_preset_file(&self, path: &Path) -> Fallible<Vec<Preset>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let mut dto: PresetFileDTO = match PresetFileFormat::from_path(path) {
+            PresetFileFormat::Toml => toml::from_str(&content)?,
+            PresetFileFormat::Json => serde_json::from_str(&content)?,
+        };
+
+        let current_version: SQLiteUserVersion = env!("CARGO_PKG_VERSION").parse()?;
+        let file_version: SQLiteUserVersion = dto.version.parse()?;
+        if file_version < current_version {
+            info!(%file_version, %current_version, "migrating preset file");
+            migrate_presets(&mut dto.presets, &file_version)?;
+        }
+
+        Ok(dto.presets.into_iter().map(Into::into).collect())
+    }
+
+    fn write_preset_file(&self, presets: Vec<Preset>, path: &Path) -> Fallible<()> {
+        let dto = PresetFileDTO {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            presets: presets.into_iter().map(Into::into).collect(),
+        };
+        let content = match PresetFileFormat::from_path(path) {
+            PresetFileFormat::Toml => toml::to_string(&dto)?,
+            PresetFileFormat::Json => serde_json::to_string_pretty(&dto)?,
+        };
+
+        let parent_dir = path.parent().context("path should have parent")?;
+        std::fs::create_dir_all(parent_dir)?;
+        std::fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BuiltinPresetsDTO {
+    entry: Vec<BuiltinPresetDTO>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BuiltinPresetDTO {
+    name: String,
+    #[serde(default = "default_sample_font_size")]
+    font_size: u32,
+    #[serde(default)]
+    custom_text: String,
+    enabled_ids: Vec<String>,
+}
+
+fn default_sample_font_size() -> u32 {
+    DEFAULT_SAMPLE_FONT_SIZE
+}
+
+impl From<BuiltinPresetDTO> for Preset {
+    fn from(dto: BuiltinPresetDTO) -> Self {
+        Preset {
+            name: dto.name,
+            template: SpecimenTemplate::with_headline_size(dto.font_size),
+            enabled_ids: dto.enabled_ids,
+            custom_text: dto.custom_text,
+            sort_mode: SortMode::default(),
+            layout: PreviewLayout::default(),
+            font_selection_mode: FontSelectionMode::AllowList,
+            disabled_ids: vec![],
+            rules: vec![],
+            font_order: vec![],
+        }
+    }
+}
+
+/// Standalone file of presets shared between users. `version` is the app version that wrote
+/// it, as in [`PreferencesDTO`].
+#[derive(Debug, Deserialize, Serialize)]
+struct PresetFileDTO {
+    version: String,
+    presets: Vec<PresetDTO>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct PreferencesDTO {
     version: String,
     presets: Vec<PresetDTO>,
+    #[serde(default)]
+    sample_text_history: Vec<String>,
+    #[serde(default = "default_font_cache_budget_mb")]
+    font_cache_budget_mb: u32,
+    #[serde(default)]
+    catalog_version: Option<u32>,
+    #[serde(default)]
+    favourite_ids: Vec<String>,
+}
+
+fn default_font_cache_budget_mb() -> u32 {
+    DEFAULT_FONT_CACHE_BUDGET_MB
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct PresetDTO {
     pub name: String,
+    /// Only in files older than 0.2.0. Replaced by `template` in [`LocalPreferencesDataSource::migrate`].
+    #[serde(default, skip_serializing)]
+    pub font_size: Option<u32>,
+    /// Only in files older than 0.3.0. Replaced by `enabled_ids` in [`LocalPreferencesDataSource::migrate`].
+    #[serde(default, skip_serializing)]
+    pub enable_paths: Option<Vec<String>>,
+    #[serde(default)]
+    pub enabled_ids: Vec<String>,
+    #[serde(default)]
+    pub custom_text: String,
+    #[serde(default)]
+    pub template: Option<SpecimenTemplateDTO>,
+    #[serde(default)]
+    pub sort_mode: SortModeDTO,
+    #[serde(default)]
+    pub layout: PreviewLayoutDTO,
+    #[serde(default)]
+    pub font_selection_mode: FontSelectionModeDTO,
+    #[serde(default)]
+    pub disabled_ids: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<FontRuleDTO>,
+    #[serde(default)]
+    pub font_order: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SpecimenTemplateDTO {
+    pub rows: Vec<SpecimenRowDTO>,
+    #[serde(default)]
+    pub fit_to_width: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SpecimenRowDTO {
+    pub kind: SpecimenRowKindDTO,
     pub font_size: u32,
-    pub enable_paths: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum SpecimenRowKindDTO {
+    Headline,
+    Body,
+    Kana,
+    Digits,
+    Latin,
 }
 
 impl From<PreferencesDTO> for Preferences {
     fn from(dto: PreferencesDTO) -> Self {
         Preferences {
             presets: dto.presets.into_iter().map(|p| p.into()).collect(),
+            sample_text_history: dto.sample_text_history,
+            font_cache_budget_mb: dto.font_cache_budget_mb,
+            catalog_version: dto.catalog_version,
+            favourite_ids: dto.favourite_ids,
         }
     }
 }
@@ -158,6 +348,10 @@ impl From<Preferences> for PreferencesDTO {
         PreferencesDTO {
             version: env!("CARGO_PKG_VERSION").to_string(),
             presets: prefs.presets.into_iter().map(|p| p.into()).collect(),
+            sample_text_history: prefs.sample_text_history,
+            font_cache_budget_mb: prefs.font_cache_budget_mb,
+            catalog_version: prefs.catalog_version,
+            favourite_ids: prefs.favourite_ids,
         }
     }
 }
@@ -166,8 +360,19 @@ impl From<PresetDTO> for Preset {
     fn from(dto: PresetDTO) -> Self {
         Preset {
             name: dto.name,
-            font_size: dto.font_size,
-            enable_paths: dto.enable_paths,
+            template: dto.template.map(Into::into).unwrap_or_else(|| {
+                SpecimenTemplate::with_headline_size(
+                    dto.font_size.unwrap_or(DEFAULT_SAMPLE_FONT_SIZE),
+                )
+            }),
+            enabled_ids: dto.enabled_ids,
+            custom_text: dto.custom_text,
+            sort_mode: dto.sort_mode.into(),
+            layout: dto.layout.into(),
+            font_selection_mode: dto.font_selection_mode.into(),
+            disabled_ids: dto.disabled_ids,
+            rules: dto.rules.into_iter().map(Into::into).collect(),
+            font_order: dto.font_order,
         }
     }
 }
@@ -176,8 +381,225 @@ impl From<Preset> for PresetDTO {
     fn from(preset: Preset) -> Self {
         PresetDTO {
             name: preset.name,
-            font_size: preset.font_size,
-            enable_paths: preset.enable_paths,
+            font_size: None,
+            enable_paths: None,
+            enabled_ids: preset.enabled_ids,
+            custom_text: preset.custom_text,
+            template: Some(preset.template.into()),
+            sort_mode: preset.sort_mode.into(),
+            layout: preset.layout.into(),
+            font_selection_mode: preset.font_selection_mode.into(),
+            disabled_ids: preset.disabled_ids,
+            rules: preset.rules.into_iter().map(Into::into).collect(),
+            font_order: preset.font_order,
+        }
+    }
+}
+
+impl From<SpecimenTemplateDTO> for SpecimenTemplate {
+    fn from(dto: SpecimenTemplateDTO) -> Self {
+        SpecimenTemplate {
+            rows: dto
+                .rows
+                .into_iter()
+                .map(|row| SpecimenRow {
+                    kind: row.kind.into(),
+                    font_size: row.font_size,
+                    enabled: row.enabled,
+                })
+                .collect(),
+            fit_to_width: dto.fit_to_width,
+        }
+    }
+}
+
+impl From<SpecimenTemplate> for SpecimenTemplateDTO {
+    fn from(template: SpecimenTemplate) -> Self {
+        SpecimenTemplateDTO {
+            rows: template
+                .rows
+                .into_iter()
+                .map(|row| SpecimenRowDTO {
+                    kind: row.kind.into(),
+                    font_size: row.font_size,
+                    enabled: row.enabled,
+                })
+                .collect(),
+            fit_to_width: template.fit_to_width,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PreviewLayoutDTO {
+    pub kind: PreviewLayoutKindDTO,
+    pub grid_columns: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum PreviewLayoutKindDTO {
+    List,
+    Grid,
+}
+
+impl Default for PreviewLayoutDTO {
+    fn default() -> Self {
+        PreviewLayout::default().into()
+    }
+}
+
+impl From<PreviewLayoutDTO> for PreviewLayout {
+    fn from(dto: PreviewLayoutDTO) -> Self {
+        PreviewLayout {
+            kind: match dto.kind {
+                PreviewLayoutKindDTO::List => PreviewLayoutKind::List,
+                PreviewLayoutKindDTO::Grid => PreviewLayoutKind::Grid,
+            },
+            grid_columns: dto.grid_columns,
+        }
+    }
+}
+
+impl From<PreviewLayout> for PreviewLayoutDTO {
+    fn from(layout: PreviewLayout) -> Self {
+        PreviewLayoutDTO {
+            kind: match layout.kind {
+                PreviewLayoutKind::List => PreviewLayoutKindDTO::List,
+                PreviewLayoutKind::Grid => PreviewLayoutKindDTO::Grid,
+            },
+            grid_columns: layout.grid_columns,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum FontSelectionModeDTO {
+    #[default]
+    AllowList,
+    DenyList,
+    Rules,
+}
+
+impl From<FontSelectionModeDTO> for FontSelectionMode {
+    fn from(dto: FontSelectionModeDTO) -> Self {
+        match dto {
+            FontSelectionModeDTO::AllowList => FontSelectionMode::AllowList,
+            FontSelectionModeDTO::DenyList => FontSelectionMode::DenyList,
+            FontSelectionModeDTO::Rules => FontSelectionMode::Rules,
+        }
+    }
+}
+
+impl From<FontSelectionMode> for FontSelectionModeDTO {
+    fn from(mode: FontSelectionMode) -> Self {
+        match mode {
+            FontSelectionMode::AllowList => FontSelectionModeDTO::AllowList,
+            FontSelectionMode::DenyList => FontSelectionModeDTO::DenyList,
+            FontSelectionMode::Rules => FontSelectionModeDTO::Rules,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum FontRuleDTO {
+    Collection { text: String },
+    Family { text: String },
+    WeightRange { min: u16, max: u16 },
+    NameRegex { pattern: String },
+    GlyphCoverage { percent: u32 },
+    SupportsCustomText,
+    Favourite,
+}
+
+impl From<FontRuleDTO> for FontRule {
+    fn from(dto: FontRuleDTO) -> Self {
+        match dto {
+            FontRuleDTO::Collection { text } => FontRule::Collection(text),
+            FontRuleDTO::Family { text } => FontRule::Family(text),
+            FontRuleDTO::WeightRange { min, max } => FontRule::WeightRange(min, max),
+            FontRuleDTO::NameRegex { pattern } => FontRule::NameRegex(pattern),
+            FontRuleDTO::GlyphCoverage { percent } => FontRule::GlyphCoverage(percent),
+            FontRuleDTO::SupportsCustomText => FontRule::SupportsCustomText,
+            FontRuleDTO::Favourite => FontRule::Favourite,
+        }
+    }
+}
+
+impl From<FontRule> for FontRuleDTO {
+    fn from(rule: FontRule) -> Self {
+        match rule {
+            FontRule::Collection(text) => FontRuleDTO::Collection { text },
+            FontRule::Family(text) => FontRuleDTO::Family { text },
+            FontRule::WeightRange(min, max) => FontRuleDTO::WeightRange { min, max },
+            FontRule::NameRegex(pattern) => FontRuleDTO::NameRegex { pattern },
+            FontRule::GlyphCoverage(percent) => FontRuleDTO::GlyphCoverage { percent },
+            FontRule::SupportsCustomText => FontRuleDTO::SupportsCustomText,
+            FontRule::Favourite => FontRuleDTO::Favourite,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum SortModeDTO {
+    #[default]
+    Catalog,
+    Name,
+    Collection,
+    WeightClass,
+    RenderedWidth,
+    Custom,
+}
+
+impl From<SortModeDTO> for SortMode {
+    fn from(dto: SortModeDTO) -> Self {
+        match dto {
+            SortModeDTO::Catalog => SortMode::Catalog,
+            SortModeDTO::Name => SortMode::Name,
+            SortModeDTO::Collection => SortMode::Collection,
+            SortModeDTO::WeightClass => SortMode::WeightClass,
+            SortModeDTO::RenderedWidth => SortMode::RenderedWidth,
+            SortModeDTO::Custom => SortMode::Custom,
+        }
+    }
+}
+
+impl From<SortMode> for SortModeDTO {
+    fn from(sort_mode: SortMode) -> Self {
+        match sort_mode {
+            SortMode::Catalog => SortModeDTO::Catalog,
+            SortMode::Name => SortModeDTO::Name,
+            SortMode::Collection => SortModeDTO::Collection,
+            SortMode::WeightClass => SortModeDTO::WeightClass,
+            SortMode::RenderedWidth => SortModeDTO::RenderedWidth,
+            SortMode::Custom => SortModeDTO::Custom,
+        }
+    }
+}
+
+impl From<SpecimenRowKindDTO> for SpecimenRowKind {
+    fn from(dto: SpecimenRowKindDTO) -> Self {
+        match dto {
+            SpecimenRowKindDTO::Headline => SpecimenRowKind::Headline,
+            SpecimenRowKindDTO::Body => SpecimenRowKind::Body,
+            SpecimenRowKindDTO::Kana => SpecimenRowKind::Kana,
+            SpecimenRowKindDTO::Digits => SpecimenRowKind::Digits,
+            SpecimenRowKindDTO::Latin => SpecimenRowKind::Latin,
+        }
+    }
+}
+
+impl From<SpecimenRowKind> for SpecimenRowKindDTO {
+    fn from(kind: SpecimenRowKind) -> Self {
+        match kind {
+            SpecimenRowKind::Headline => SpecimenRowKindDTO::Headline,
+            SpecimenRowKind::Body => SpecimenRowKindDTO::Body,
+            SpecimenRowKind::Kana => SpecimenRowKindDTO::Kana,
+            SpecimenRowKind::Digits => SpecimenRowKindDTO::Digits,
+            SpecimenRowKind::Latin => SpecimenRowKindDTO::Latin,
         }
     }
 }
@@ -201,16 +623,61 @@ mod tests {
         let prefs = Preferences {
             presets: vec![Preset {
                 name: "test".to_string(),
-                font_size: 16,
-                enable_paths: vec!["/path".to_string()],
+                template: SpecimenTemplate::with_headline_size(16),
+                enabled_ids: vec!["uVeiwE".to_string()],
+                custom_text: "タイトル\nサブタイトル".to_string(),
+                sort_mode: SortMode::Name,
+                layout: PreviewLayout {
+                    kind: PreviewLayoutKind::Grid,
+                    grid_columns: 6,
+                },
+                font_selection_mode: FontSelectionMode::DenyList,
+                disabled_ids: vec!["uVeiwR".to_string()],
+                rules: vec![
+                    FontRule::Collection("桜".to_string()),
+                    FontRule::WeightRange(100, 500),
+                    FontRule::SupportsCustomText,
+                    FontRule::Favourite,
+                ],
+                font_order: vec!["uVeiwR".to_string(), "uVeiwE".to_string()],
             }],
+            sample_text_history: vec!["タイトル\nサブタイトル".to_string()],
+            font_cache_budget_mb: 64,
+            catalog_version: Some(2),
+            favourite_ids: vec!["uVeiwR".to_string()],
         };
         ds.save(prefs).unwrap();
         let loaded = ds.retrieve().unwrap();
         assert_eq!(loaded.presets.len(), 1);
         assert_eq!(loaded.presets[0].name, "test");
-        assert_eq!(loaded.presets[0].font_size, 16);
-        assert_eq!(loaded.presets[0].enable_paths, vec!["/path".to_string()]);
+        assert_eq!(loaded.presets[0].template.headline_size(), 16);
+        assert_eq!(loaded.presets[0].enabled_ids, vec!["uVeiwE".to_string()]);
+        assert_eq!(loaded.presets[0].custom_text, "タイトル\nサブタイトル");
+        assert_eq!(loaded.sample_text_history, vec!["タイトル\nサブタイトル"]);
+        assert_eq!(loaded.presets[0].sort_mode, SortMode::Name);
+        assert_eq!(loaded.presets[0].layout.kind, PreviewLayoutKind::Grid);
+        assert_eq!(loaded.presets[0].layout.grid_columns, 6);
+        assert_eq!(loaded.font_cache_budget_mb, 64);
+        assert_eq!(loaded.catalog_version, Some(2));
+        assert_eq!(loaded.favourite_ids, vec!["uVeiwR".to_string()]);
+        assert_eq!(
+            loaded.presets[0].font_selection_mode,
+            FontSelectionMode::DenyList
+        );
+        assert_eq!(loaded.presets[0].disabled_ids, vec!["uVeiwR".to_string()]);
+        assert_eq!(
+            loaded.presets[0].rules,
+            vec![
+                FontRule::Collection("桜".to_string()),
+                FontRule::WeightRange(100, 500),
+                FontRule::SupportsCustomText,
+                FontRule::Favourite,
+            ],
+        );
+        assert_eq!(
+            loaded.presets[0].font_order,
+            vec!["uVeiwR".to_string(), "uVeiwE".to_string()],
+        );
     }
 
     #[test]
@@ -219,7 +686,7 @@ mod tests {
         let ds = LocalPreferencesDataSource::with_pathname(
             dir.path().join("nested").join("dir").join("prefs.toml"),
         );
-        let prefs = Preferences { presets: vec![] };
+        let prefs = Preferences::default();
         ds.save(prefs).unwrap();
         let loaded = ds.retrieve().unwrap();
         assert!(loaded.presets.is_empty());
@@ -236,7 +703,7 @@ mod tests {
              [[presets]]\n\
              name = \"old\"\n\
              font_size = 12\n\
-             enable_paths = [\"/old\"]\n",
+             enable_paths = [\"./桜/02_uヴュー(12)/UビューE.ttf\", \"/old\"]\n",
         )
         .unwrap();
 
@@ -245,11 +712,137 @@ mod tests {
 
         assert_eq!(prefs.presets.len(), 1);
         assert_eq!(prefs.presets[0].name, "old");
-        assert_eq!(prefs.presets[0].font_size, 12);
+        assert_eq!(prefs.presets[0].template.headline_size(), 12);
+        assert_eq!(prefs.presets[0].custom_text, "");
+        assert_eq!(prefs.presets[0].enabled_ids, vec!["uVeiwE", "/old"]);
+        assert!(prefs.sample_text_history.is_empty());
+        assert_eq!(prefs.presets[0].sort_mode, SortMode::Catalog);
+        assert_eq!(prefs.presets[0].layout, PreviewLayout::default());
+        assert_eq!(prefs.font_cache_budget_mb, DEFAULT_FONT_CACHE_BUDGET_MB);
+        assert_eq!(prefs.catalog_version, None);
+        assert_eq!(
+            prefs.presets[0].font_selection_mode,
+            FontSelectionMode::AllowList
+        );
 
         let content = std::fs::read_to_string(&path).unwrap();
         let dto: toml::Value = toml::from_str(&content).unwrap();
         assert_eq!(dto["version"].as_str().unwrap(), env!("CARGO_PKG_VERSION"));
+        assert!(dto["presets"][0].get("font_size").is_none());
+        assert!(dto["presets"][0].get("enable_paths").is_none());
+        assert_eq!(
+            dto["presets"][0]["template"]["rows"][0]["font_size"].as_integer(),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn retrieve_migrated_template_shows_only_headline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prefs.toml");
+        std::fs::write(
+            &path,
+            "version = \"0.1.0\"\n\n\
+             [[presets]]\n\
+             name = \"old\"\n\
+             font_size = 12\n",
+        )
+        .unwrap();
+
+        let ds = LocalPreferencesDataSource::with_pathname(path.clone());
+        let prefs = ds.retrieve().unwrap();
+
+        let enabled = prefs.presets[0]
+            .template
+            .rows
+            .iter()
+            .filter(|row| row.enabled)
+            .map(|row| row.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(enabled, vec![SpecimenRowKind::Headline]);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let dto: toml::Value = toml::from_str(&content).unwrap();
+        let rows = dto["presets"][0]["template"]["rows"].as_array().unwrap();
+        assert_eq!(rows[0]["enabled"].as_bool(), Some(true));
+        assert!(
+            rows[1..]
+                .iter()
+                .all(|row| row["enabled"].as_bool() == Some(false))
+        );
+    }
+
+    #[test]
+    fn builtin_presets_refer_to_catalog_fonts() {
+        let dir = tempfile::tempdir().unwrap();
+        let ds = LocalPreferencesDataSource::with_pathname(dir.path().join("prefs.toml"));
+        let asset = Asset::get("font_list.toml").unwrap();
+        let catalog: toml::Value = toml::from_str(from_utf8(&asset.data).unwrap()).unwrap();
+        let catalog_ids = catalog["entry"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.get("id").unwrap_or(&entry["name"]).as_str().unwrap())
+            .collect::<Vec<_>>();
+
+        let presets = ds.find_builtin_presets();
+
+        assert!(presets.iter().any(|preset| preset.name == "毛筆"));
+        for preset in presets {
+            assert!(!preset.enabled_ids.is_empty(), "{}", preset.name);
+            for id in &preset.enabled_ids {
+                assert!(catalog_ids.contains(&id.as_str()), "{}: {id}", preset.name);
+            }
+        }
+    }
+
+    #[test]
+    fn preset_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let ds = LocalPreferencesDataSource::with_pathname(dir.path().join("prefs.toml"));
+        let preset = Preset {
+            name: "ブラシ系".to_string(),
+            template: SpecimenTemplate::with_headline_size(40),
+            enabled_ids: vec!["uVeiwE".to_string()],
+            custom_text: "タイトル".to_string(),
+            sort_mode: SortMode::Name,
+            font_selection_mode: FontSelectionMode::AllowList,
+            ..Default::default()
+        };
+
+        for file_name in ["presets.toml", "presets.json"] {
+            let path = dir.path().join("shared").join(file_name);
+            ds.write_preset_file(vec![preset.clone()], &path).unwrap();
+            let loaded = ds.read_preset_file(&path).unwrap();
+
+            assert_eq!(loaded.len(), 1);
+            assert_eq!(loaded[0].name, "ブラシ系");
+            assert_eq!(loaded[0].template.headline_size(), 40);
+            assert_eq!(loaded[0].enabled_ids, vec!["uVeiwE"]);
+            assert_eq!(loaded[0].sort_mode, SortMode::Name);
+        }
+
+        let content = std::fs::read_to_string(dir.path().join("shared/presets.json")).unwrap();
+        assert!(content.contains(&format!("\"version\": \"{}\"", env!("CARGO_PKG_VERSION"))));
+    }
+
+    #[test]
+    fn read_preset_file_migrates_old_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let ds = LocalPreferencesDataSource::with_pathname(dir.path().join("prefs.toml"));
+        let path = dir.path().join("old.json");
+        std::fs::write(
+            &path,
+            r#"{"version": "0.1.0", "presets": [{"name": "old", "font_size": 12,
+                "enable_paths": ["./桜/02_uヴュー(12)/UビューE.ttf"]}]}"#,
+        )
+        .unwrap();
+
+        let presets = ds.read_preset_file(&path).unwrap();
+
+        assert_eq!(presets[0].name, "old");
+        assert_eq!(presets[0].template.headline_size(), 12);
+        assert_eq!(presets[0].enabled_ids, vec!["uVeiwE"]);
     }
 
     #[test]
@@ -259,9 +852,12 @@ mod tests {
         let prefs = Preferences {
             presets: vec![Preset {
                 name: "current".to_string(),
-                font_size: 20,
-                enable_paths: vec![],
+                ..Default::default()
             }],
+            sample_text_history: vec![],
+            font_cache_budget_mb: DEFAULT_FONT_CACHE_BUDGET_MB,
+            catalog_version: None,
+            favourite_ids: vec![],
         };
         ds.save(prefs).unwrap();
 
@@ -0,0 +1,176 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::{FontSelectionMode, Preset, SortMode, SpecimenTemplate};
+use crate::prelude::*;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+use std::io::prelude::*;
+
+/// Prefix of a share code, followed by the format version and a `.`.
+const SHARE_CODE_PREFIX: &str = "f66p";
+
+/// Format version of the codes written by [`encode_share_code`].
+const SHARE_CODE_VERSION: u32 = 1;
+
+/// Upper bound of a decoded code, so that a crafted code cannot exhaust memory.
+const SHARE_CODE_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Encodes the name, headline size, enabled fonts, custom text and, with [`SortMode::Custom`],
+/// the arranged order of the enabled fonts of `preset` as a single line of URL-safe text to be
+/// pasted into chat, e.g. `f66p1.<base64>`.
+pub fn encode_share_code(preset: &Preset) -> Fallible<String> {
+    let font_order = match preset.sort_mode {
+        SortMode::Custom => preset
+            .font_order
+            .iter()
+            .filter(|id| preset.enabled_ids.contains(id))
+            .cloned()
+            .collect(),
+        _ => vec![],
+    };
+    let dto = ShareCodeDTO {
+        name: preset.name.clone(),
+        font_size: preset.template.headline_size(),
+        enabled_ids: preset.enabled_ids.clone(),
+        custom_text: preset.custom_text.clone(),
+        font_order,
+    };
+
+    let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+    encoder.write_all(toml::to_string(&dto)?.as_bytes())?;
+    let data = encoder.finish()?;
+
+    Ok(format!(
+        "{SHARE_CODE_PREFIX}{SHARE_CODE_VERSION}.{}",
+        URL_SAFE_NO_PAD.encode(data)
+    ))
+}
+
+/// Decodes a code written by [`encode_share_code`]. Whitespace is ignored since chat clients
+/// may wrap long codes.
+pub fn decode_share_code(code: &str) -> Fallible<Preset> {
+    let code = code
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let (version, payload) = code
+        .strip_prefix(SHARE_CODE_PREFIX)
+        .and_then(|data| data.split_once('.'))
+        .context("not a preset code")?;
+    let version: u32 = version.parse().context("not a preset code")?;
+    if version > SHARE_CODE_VERSION {
+        bail!("the preset code was made by a newer version");
+    }
+
+    let data = URL_SAFE_NO_PAD
+        .decode(payload)
+        .context("the preset code is broken")?;
+    let mut content = String::new();
+    DeflateDecoder::new(data.as_slice())
+        .take(SHARE_CODE_MAX_BYTES)
+        .read_to_string(&mut content)
+        .context("the preset code is broken")?;
+    let dto: ShareCodeDTO = toml::from_str(&content).context("the preset code is broken")?;
+
+    Ok(Preset {
+        name: dto.name,
+        template: SpecimenTemplate::with_headline_size(dto.font_size),
+        enabled_ids: dto.enabled_ids,
+        custom_text: dto.custom_text,
+        sort_mode: if dto.font_order.is_empty() {
+            SortMode::default()
+        } else {
+            SortMode::Custom
+        },
+        font_selection_mode: FontSelectionMode::AllowList,
+        font_order: dto.font_order,
+        ..Default::default()
+    })
+}
+
+/// Short keys keep the code small enough for chat.
+#[derive(Debug, Deserialize, Serialize)]
+struct ShareCodeDTO {
+    #[serde(rename = "n")]
+    name: String,
+    #[serde(rename = "s")]
+    font_size: u32,
+    #[serde(rename = "f", default)]
+    enabled_ids: Vec<String>,
+    #[serde(rename = "t", default)]
+    custom_text: String,
+    /// Only written for presets in [`SortMode::Custom`]. Older versions ignore it.
+    #[serde(rename = "o", default, skip_serializing_if = "Vec::is_empty")]
+    font_order: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_code_roundtrip() {
+        let preset = Preset {
+            name: "太ゴシック".to_string(),
+            template: SpecimenTemplate::with_headline_size(40),
+            enabled_ids: vec!["uVeiwE".to_string(), "uVeiwR".to_string()],
+            custom_text: "タイトル\nサブタイトル".to_string(),
+            sort_mode: SortMode::Name,
+            font_selection_mode: FontSelectionMode::AllowList,
+            ..Default::default()
+        };
+
+        let code = encode_share_code(&preset).unwrap();
+        assert!(code.starts_with("f66p1."));
+
+        let wrapped = format!("{}\n  {}", &code[..10], &code[10..]);
+        let decoded = decode_share_code(&wrapped).unwrap();
+        assert_eq!(decoded.name, "太ゴシック");
+        assert_eq!(decoded.template.headline_size(), 40);
+        assert_eq!(decoded.enabled_ids, preset.enabled_ids);
+        assert_eq!(decoded.custom_text, preset.custom_text);
+        assert_eq!(decoded.sort_mode, SortMode::default());
+        assert!(decoded.font_order.is_empty());
+    }
+
+    #[test]
+    fn share_code_keeps_arranged_order() {
+        let preset = Preset {
+            name: "太ゴシック".to_string(),
+            enabled_ids: vec!["uVeiwE".to_string(), "uVeiwR".to_string()],
+            sort_mode: SortMode::Custom,
+            font_order: ["uVeiwR", "hidden", "uVeiwE"].map(String::from).to_vec(),
+            ..Default::default()
+        };
+
+        let decoded = decode_share_code(&encode_share_code(&preset).unwrap()).unwrap();
+
+        assert_eq!(decoded.sort_mode, SortMode::Custom);
+        assert_eq!(decoded.font_order, vec!["uVeiwR", "uVeiwE"]);
+    }
+
+    #[test]
+    fn decode_share_code_rejects_unknown_codes() {
+        assert!(decode_share_code("hello").is_err());
+        assert!(decode_share_code("f66p1.!!!").is_err());
+        assert!(decode_share_code("f66p2.AAAA").is_err());
+    }
+}
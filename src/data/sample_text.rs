@@ -0,0 +1,90 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::model::SampleText;
+use crate::prelude::*;
+use directories::ProjectDirs;
+
+#[cfg(test)]
+mod fake_sample_text;
+
+mod local_sample_text;
+
+trait SampleTextDataSource {
+    /// Sample texts shipped with the app.
+    fn find_builtin(&self) -> Vec<SampleText>;
+
+    /// Sample texts added by the user.
+    fn retrieve_user_defined(&self) -> Fallible<Vec<SampleText>>;
+
+    fn save_user_defined(&self, entries: Vec<SampleText>) -> Fallible<()>;
+}
+
+pub struct SampleTextRepository {
+    #[cfg(not(test))]
+    data_source: local_sample_text::LocalSampleTextDataSource,
+
+    #[cfg(test)]
+    data_source: fake_sample_text::FakeSampleTextDataSource,
+}
+
+impl SampleTextRepository {
+    #[cfg_attr(test, allow(unused_variables))]
+    pub fn new(project: &ProjectDirs) -> Self {
+        #[cfg(not(test))]
+        return Self {
+            data_source: local_sample_text::LocalSampleTextDataSource::new(project),
+        };
+
+        #[cfg(test)]
+        return Self {
+            data_source: fake_sample_text::FakeSampleTextDataSource::new(),
+        };
+    }
+
+    /// Returns the built-in sample texts followed by the user-defined ones.
+    ///
+    /// User-defined sample texts that could not be read are left out so that the built-in ones
+    /// are still available.
+    pub fn find_all(&self) -> Vec<SampleText> {
+        let mut entries = self.data_source.find_builtin();
+        match self.data_source.retrieve_user_defined() {
+            Ok(user_defined) => entries.extend(user_defined),
+            Err(e) => warn!(?e, "failed to read user-defined sample texts"),
+        }
+        entries
+    }
+
+    pub fn save_user_defined(&self, entries: Vec<SampleText>) -> Fallible<()> {
+        self.data_source.save_user_defined(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_all_keeps_builtin_when_user_defined_is_unreadable() {
+        let repo = SampleTextRepository::new(
+            &ProjectDirs::from("com", "sukawasatoru", "fonts66-viewer").unwrap(),
+        );
+        repo.data_source.set_retrieve_error(true);
+
+        let entries = repo.find_all();
+
+        assert_eq!(entries, repo.data_source.find_builtin());
+    }
+}
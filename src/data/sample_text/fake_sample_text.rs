@@ -0,0 +1,61 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::data::sample_text::SampleTextDataSource;
+use crate::model::SampleText;
+use crate::prelude::*;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub struct FakeSampleTextDataSource {
+    user_defined: Mutex<Vec<SampleText>>,
+    retrieve_error: AtomicBool,
+}
+
+impl FakeSampleTextDataSource {
+    pub fn new() -> Self {
+        Self {
+            user_defined: Mutex::new(vec![]),
+            retrieve_error: AtomicBool::new(false),
+        }
+    }
+
+    /// Makes `retrieve_user_defined` fail as if the file were malformed.
+    pub fn set_retrieve_error(&self, value: bool) {
+        self.retrieve_error.store(value, Ordering::Relaxed);
+    }
+}
+
+impl SampleTextDataSource for FakeSampleTextDataSource {
+    fn find_builtin(&self) -> Vec<SampleText> {
+        vec![SampleText {
+            name: "いろは歌".into(),
+            text: "いろはにほへと ちりぬるを".into(),
+            user_defined: false,
+        }]
+    }
+
+    fn retrieve_user_defined(&self) -> Fallible<Vec<SampleText>> {
+        if self.retrieve_error.load(Ordering::Relaxed) {
+            bail!("malformed sample texts");
+        }
+        Ok(self.user_defined.lock().unwrap().clone())
+    }
+
+    fn save_user_defined(&self, entries: Vec<SampleText>) -> Fallible<()> {
+        *self.user_defined.lock().unwrap() = entries;
+        Ok(())
+    }
+}
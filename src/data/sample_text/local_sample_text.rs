@@ -0,0 +1,211 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::asset::Asset;
+use crate::data::sample_text::SampleTextDataSource;
+use crate::model::SampleText;
+use crate::prelude::*;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::from_utf8;
+use std::sync::{Mutex, MutexGuard};
+
+/// Reads the built-in library from the embedded `sample_texts.toml` and the user-defined entries
+/// from `sample_texts.toml` in the config dir. The user file uses the same format, so it can be
+/// edited by hand as well.
+pub struct LocalSampleTextDataSource {
+    builtin: Vec<SampleText>,
+    mutex: Mutex<()>,
+    pathname: PathBuf,
+}
+
+impl LocalSampleTextDataSource {
+    #[cfg_attr(test, allow(unused))]
+    pub fn new(project: &ProjectDirs) -> Self {
+        Self::with_pathname(project.config_dir().join("sample_texts.toml"))
+    }
+
+    fn with_pathname(pathname: PathBuf) -> Self {
+        let asset = Asset::get("sample_texts.toml").expect("sample_texts.toml is not found");
+        let content = from_utf8(&asset.data).expect("sample_texts.toml is not valid utf-8");
+        let builtin = toml::from_str::<SampleTextsDTO>(content)
+            .expect("sample_texts.toml is invalid")
+            .entry
+            .into_iter()
+            .map(|dto| dto.into_sample_text(false))
+            .collect();
+
+        Self {
+            builtin,
+            mutex: Mutex::new(()),
+            pathname,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        match self.mutex.lock() {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(?e, "try clear poisoned state");
+                self.mutex.clear_poison();
+                self.mutex.lock().expect("poisoned")
+            }
+        }
+    }
+}
+
+impl SampleTextDataSource for LocalSampleTextDataSource {
+    fn find_builtin(&self) -> Vec<SampleText> {
+        self.builtin.clone()
+    }
+
+    fn retrieve_user_defined(&self) -> Fallible<Vec<SampleText>> {
+        let _guard = self.lock();
+
+        if !self.pathname.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = std::fs::read_to_string(&self.pathname)?;
+        let dto: SampleTextsDTO = toml::from_str(&content)?;
+        Ok(dto
+            .entry
+            .into_iter()
+            .map(|dto| dto.into_sample_text(true))
+            .collect())
+    }
+
+    fn save_user_defined(&self, entries: Vec<SampleText>) -> Fallible<()> {
+        let _guard = self.lock();
+
+        let parent_dir = self
+            .pathname
+            .parent()
+            .context("pathname should have parent")?;
+        std::fs::create_dir_all(parent_dir)?;
+
+        // The entries to save don't include those of a file that failed to load, so keep it
+        // aside rather than losing the user's edits.
+        if self.pathname.exists() {
+            let readable = std::fs::read_to_string(&self.pathname)
+                .ok()
+                .is_some_and(|content| toml::from_str::<SampleTextsDTO>(&content).is_ok());
+            if !readable {
+                let backup = self.pathname.with_extension("toml.bak");
+                warn!(path = %backup.display(), "back up unreadable sample texts");
+                std::fs::copy(&self.pathname, backup)?;
+            }
+        }
+
+        let dto = SampleTextsDTO {
+            entry: entries
+                .into_iter()
+                .map(|entry| SampleTextDTO {
+                    name: entry.name,
+                    text: entry.text,
+                })
+                .collect(),
+        };
+        std::fs::write(&self.pathname, toml::to_string(&dto)?)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SampleTextsDTO {
+    #[serde(default)]
+    entry: Vec<SampleTextDTO>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SampleTextDTO {
+    name: String,
+    text: String,
+}
+
+impl SampleTextDTO {
+    fn into_sample_text(self, user_defined: bool) -> SampleText {
+        SampleText {
+            name: self.name,
+            text: self.text,
+            user_defined,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_builtin_loads_embedded_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let ds = LocalSampleTextDataSource::with_pathname(dir.path().join("sample_texts.toml"));
+
+        let builtin = ds.find_builtin();
+
+        assert!(builtin.iter().any(|entry| entry.name == "いろは歌"));
+        assert!(builtin.iter().all(|entry| !entry.user_defined));
+        assert!(builtin.iter().all(|entry| !entry.text.starts_with('\n')));
+    }
+
+    #[test]
+    fn save_and_retrieve_user_defined_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let ds = LocalSampleTextDataSource::with_pathname(
+            dir.path().join("nested").join("sample_texts.toml"),
+        );
+        assert!(ds.retrieve_user_defined().unwrap().is_empty());
+
+        ds.save_user_defined(vec![SampleText {
+            name: "Title".into(),
+            text: "タイトル\nサブタイトル".into(),
+            user_defined: true,
+        }])
+        .unwrap();
+
+        let loaded = ds.retrieve_user_defined().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "Title");
+        assert_eq!(loaded[0].text, "タイトル\nサブタイトル");
+        assert!(loaded[0].user_defined);
+    }
+
+    #[test]
+    fn save_user_defined_backs_up_unreadable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let pathname = dir.path().join("sample_texts.toml");
+        std::fs::write(&pathname, "[[entry]]\nname = ").unwrap();
+        let ds = LocalSampleTextDataSource::with_pathname(pathname.clone());
+        assert!(ds.retrieve_user_defined().is_err());
+
+        ds.save_user_defined(vec![]).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("sample_texts.toml.bak")).unwrap(),
+            "[[entry]]\nname = "
+        );
+        assert!(ds.retrieve_user_defined().unwrap().is_empty());
+
+        ds.save_user_defined(vec![]).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("sample_texts.toml.bak")).unwrap(),
+            "[[entry]]\nname = "
+        );
+    }
+}
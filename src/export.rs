@@ -0,0 +1,204 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+pub use html_specimen::export_html_specimen;
+pub use pdf_specimen::export_pdf_specimen;
+pub use preview_image::render_preview_image;
+pub use subset::export_font_subsets;
+pub use svg_outline::export_svg_outline;
+
+mod html_specimen;
+mod outline;
+mod pdf;
+mod pdf_specimen;
+mod preview_image;
+mod subset;
+mod svg_outline;
+
+use crate::model::{FontEntry, FontFacts, FontFile};
+use crate::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Contents of a preset to be exported.
+pub struct PresetExport {
+    pub name: String,
+    pub font_size: u32,
+    pub fonts: Vec<SpecimenFont>,
+    pub custom_text: String,
+}
+
+/// A font of the preset together with its file, if it could be found on this system.
+pub struct SpecimenFont {
+    pub entry: FontEntry,
+    pub file: Option<FontFile>,
+}
+
+/// Returns `{dir}/{stem}.{ext}`, or `{dir}/{stem} (N).{ext}` if the file already exists, so that
+/// an export never overwrites a file in the user's folder.
+pub fn unique_path(dir: &Path, stem: &str, ext: &str) -> PathBuf {
+    unique(dir, stem, |stem| format!("{stem}.{ext}"))
+}
+
+/// Same as [`unique_path`] for a directory.
+fn unique_dir(dir: &Path, stem: &str) -> PathBuf {
+    unique(dir, stem, |stem| stem.to_string())
+}
+
+fn unique(dir: &Path, stem: &str, file_name: impl Fn(&str) -> String) -> PathBuf {
+    let stem = sanitize_file_stem(stem);
+    let path = dir.join(file_name(&stem));
+    if !path.exists() {
+        return path;
+    }
+    for i in 2.. {
+        let path = dir.join(file_name(&format!("{stem} ({i})")));
+        if !path.exists() {
+            return path;
+        }
+    }
+    unreachable!()
+}
+
+/// Reads the weight class and the characters mapped by the Unicode `cmap` subtables of `file`.
+pub fn read_font_facts(file: &FontFile) -> Fallible<FontFacts> {
+    let face =
+        rustybuzz::Face::from_slice(&file.data, file.index).context("failed to parse font file")?;
+
+    let mut chars = vec![];
+    for subtable in face
+        .tables()
+        .cmap
+        .into_iter()
+        .flat_map(|cmap| cmap.subtables)
+    {
+        if !subtable.is_unicode() {
+            continue;
+        }
+        subtable.codepoints(|codepoint| {
+            if let Some(c) = char::from_u32(codepoint)
+                && subtable.glyph_index(codepoint).is_some()
+            {
+                chars.push(c);
+            }
+        });
+    }
+    chars.sort_unstable();
+    chars.dedup();
+
+    Ok(FontFacts {
+        weight_class: face.weight().to_number(),
+        chars,
+    })
+}
+
+/// Replaces characters that are not allowed in file names on Windows or macOS.
+fn sanitize_file_stem(stem: &str) -> String {
+    let sanitized = stem
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>();
+    if sanitized.is_empty() {
+        "untitled".to_string()
+    } else {
+        sanitized
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats a coordinate with up to two decimals, as used in PDF and SVG paths.
+fn number(value: f32) -> String {
+    let s = format!("{value:.2}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Fallible<()> {
+    let parent_dir = path.parent().context("path should have parent")?;
+    std::fs::create_dir_all(parent_dir)?;
+    std::fs::write(path, data).with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_path_appends_number_when_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = unique_path(dir.path(), "Preset 1", "pdf");
+        assert_eq!(first, dir.path().join("Preset 1.pdf"));
+
+        std::fs::write(&first, b"").unwrap();
+        let second = unique_path(dir.path(), "Preset 1", "pdf");
+        assert_eq!(second, dir.path().join("Preset 1 (2).pdf"));
+    }
+
+    #[test]
+    fn number_trims_trailing_zeros() {
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(1.5), "1.5");
+        assert_eq!(number(-0.001), "0");
+        assert_eq!(number(12.345), "12.35");
+    }
+
+    #[test]
+    fn escape_xml_escapes_markup() {
+        assert_eq!(
+            escape_xml("<a href=\"x\">&'"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;"
+        );
+    }
+
+    #[test]
+    fn unique_dir_appends_number_when_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("Preset 1")).unwrap();
+
+        assert_eq!(
+            unique_dir(dir.path(), "Preset 1"),
+            dir.path().join("Preset 1 (2)")
+        );
+    }
+
+    #[test]
+    fn sanitize_file_stem_replaces_separators() {
+        assert_eq!(sanitize_file_stem("a/b\\c:d"), "a_b_c_d");
+        assert_eq!(sanitize_file_stem("  "), "untitled");
+        assert_eq!(sanitize_file_stem("ブラシ系"), "ブラシ系");
+    }
+}
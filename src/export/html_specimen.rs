@@ -0,0 +1,320 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::export::subset::extract_face;
+use crate::export::{PresetExport, escape_xml, unique_dir, unique_path, write_file};
+use crate::prelude::*;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Writes a self-contained specimen site of `preset` into a new folder in `dir` and returns the
+/// path of its `index.html`.
+///
+/// The folder contains `index.html`, `style.css` and a copy of every font file under `fonts/`,
+/// with one `@font-face` rule per font named after its `font_name`. Faces of a TrueType Collection
+/// are extracted into a standalone font since browsers do not load collections. Fonts whose file
+/// could not be found or extracted fall back to `local()`.
+pub fn export_html_specimen(dir: &Path, preset: &PresetExport) -> Fallible<PathBuf> {
+    let out_dir = unique_dir(dir, &preset.name);
+
+    let mut font_faces = Vec::with_capacity(preset.fonts.len());
+    for font in &preset.fonts {
+        let font_name = font.entry.font_name;
+        let data = font
+            .file
+            .as_ref()
+            .and_then(|file| match extract_face(&file.data, file.index) {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    warn!(?e, font_name, "failed to extract font face");
+                    None
+                }
+            });
+        let src = match data {
+            Some(data) => {
+                // Names that sanitize to the same stem, e.g. faces of one collection, get a
+                // counter so that they don't overwrite each other.
+                let (ext, format) = font_file_format(&data);
+                let path = unique_path(&out_dir.join("fonts"), font_name, ext);
+                write_file(&path, &data)?;
+                let file_name = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or_default();
+                format!("url(\"fonts/{file_name}\") format(\"{format}\")")
+            }
+            None => format!("local({})", css_string(font_name)),
+        };
+        font_faces.push(src);
+    }
+
+    write_file(
+        &out_dir.join("style.css"),
+        render_css(preset, &font_faces).as_bytes(),
+    )?;
+
+    let index = out_dir.join("index.html");
+    write_file(&index, render_html(preset).as_bytes())?;
+    info!(path = %index.display(), "exported html specimen");
+    Ok(index)
+}
+
+/// Returns the file extension and the CSS `format()` hint for the font `data`.
+fn font_file_format(data: &[u8]) -> (&'static str, &'static str) {
+    match data.get(..4) {
+        Some(b"OTTO") => ("otf", "opentype"),
+        Some(b"wOFF") => ("woff", "woff"),
+        Some(b"wOF2") => ("woff2", "woff2"),
+        _ => ("ttf", "truetype"),
+    }
+}
+
+fn render_css(preset: &PresetExport, font_faces: &[String]) -> String {
+    let mut css = String::new();
+    for (font, src) in preset.fonts.iter().zip(font_faces) {
+        let _ = writeln!(
+            css,
+            "@font-face {{\n  font-family: {};\n  src: {src};\n  font-display: swap;\n}}\n",
+            css_string(font.entry.font_name),
+        );
+    }
+    for (index, font) in preset.fonts.iter().enumerate() {
+        let _ = writeln!(
+            css,
+            ".font-{index} {{\n  font-family: {}, sans-serif;\n}}\n",
+            css_string(font.entry.font_name),
+        );
+    }
+    css.push_str(
+        r#"body {
+  margin: 0;
+  font-family: sans-serif;
+  color: #222;
+}
+
+header {
+  position: sticky;
+  top: 0;
+  display: flex;
+  gap: 16px;
+  align-items: center;
+  padding: 8px 24px;
+  background: rgba(255, 255, 255, 0.9);
+  border-bottom: 1px solid #ddd;
+}
+
+header h1 {
+  margin: 0;
+  font-size: 18px;
+}
+
+header textarea {
+  width: 40em;
+  height: 3em;
+}
+
+section {
+  padding: 16px 24px;
+  border-bottom: 1px solid #ddd;
+}
+
+section h2 {
+  margin: 0;
+  font-size: 14px;
+}
+
+section .font-name {
+  margin: 4px 0;
+  color: #777;
+  font-size: 12px;
+}
+
+.sample {
+  margin: 8px 0 0;
+  white-space: pre-wrap;
+  overflow-wrap: anywhere;
+}
+"#,
+    );
+    css
+}
+
+fn render_html(preset: &PresetExport) -> String {
+    let name = escape_xml(&preset.name);
+    let custom_text = escape_xml(&preset.custom_text);
+    let font_size = preset.font_size;
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="ja">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{name}</title>
+<link rel="stylesheet" href="style.css">
+</head>
+<body>
+<header>
+<h1>{name}</h1>
+<textarea id="sample-text">{custom_text}</textarea>
+<label>Size <input id="sample-size" type="number" min="1" max="1000" value="{font_size}"> px</label>
+</header>
+<main>
+"#,
+    );
+
+    for (index, font) in preset.fonts.iter().enumerate() {
+        let title = font
+            .entry
+            .display_name
+            .as_deref()
+            .unwrap_or(font.entry.font_name);
+        let _ = write!(
+            html,
+            r#"<section>
+<h2>{}</h2>
+<p class="font-name"><code>font-family: {};</code></p>
+<p class="sample font-{index}" style="font-size: {font_size}px">{custom_text}</p>
+</section>
+"#,
+            escape_xml(title),
+            escape_xml(&css_string(font.entry.font_name)),
+        );
+    }
+
+    html.push_str(
+        r#"</main>
+<script>
+const text = document.getElementById("sample-text");
+const size = document.getElementById("sample-size");
+const update = () => {
+  for (const sample of document.querySelectorAll(".sample")) {
+    sample.textContent = text.value;
+    sample.style.fontSize = `${size.value}px`;
+  }
+};
+text.addEventListener("input", update);
+size.addEventListener("input", update);
+</script>
+</body>
+</html>
+"#,
+    );
+    html
+}
+
+fn css_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::SpecimenFont;
+    use crate::model::{FontEntry, FontFile};
+
+    fn preset() -> PresetExport {
+        PresetExport {
+            name: "ブラシ系".into(),
+            font_size: 32,
+            fonts: vec![
+                SpecimenFont {
+                    entry: FontEntry::new("Arial".into(), "./arial.ttf".into(), None, "Arial"),
+                    file: Some(FontFile::new(b"\x00\x01\x00\x00dummy".to_vec(), 0)),
+                },
+                SpecimenFont {
+                    entry: FontEntry::new(
+                        "Times New Roman".into(),
+                        "./times.ttf".into(),
+                        None,
+                        "Times New Roman",
+                    ),
+                    file: None,
+                },
+            ],
+            custom_text: "<title>".into(),
+        }
+    }
+
+    #[test]
+    fn export_writes_html_css_and_fonts() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let index = export_html_specimen(dir.path(), &preset()).unwrap();
+
+        let out_dir = dir.path().join("ブラシ系");
+        assert_eq!(index, out_dir.join("index.html"));
+        assert_eq!(
+            std::fs::read(out_dir.join("fonts").join("Arial.ttf")).unwrap(),
+            b"\x00\x01\x00\x00dummy"
+        );
+        assert!(!out_dir.join("fonts").join("Times New Roman.ttf").exists());
+
+        let css = std::fs::read_to_string(out_dir.join("style.css")).unwrap();
+        assert!(css.contains(r#"src: url("fonts/Arial.ttf") format("truetype");"#));
+        assert!(css.contains(r#"src: local("Times New Roman");"#));
+
+        let html = std::fs::read_to_string(index).unwrap();
+        assert!(
+            html.contains(r#"<p class="sample font-1" style="font-size: 32px">&lt;title&gt;</p>"#)
+        );
+    }
+
+    #[test]
+    fn export_keeps_fonts_with_the_same_stem_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut preset = preset();
+        preset.fonts[1] = SpecimenFont {
+            entry: FontEntry::new("Arial ".into(), "./arial2.ttf".into(), None, "Arial "),
+            file: Some(FontFile::new(b"\x00\x01\x00\x00other".to_vec(), 0)),
+        };
+
+        export_html_specimen(dir.path(), &preset).unwrap();
+
+        let fonts_dir = dir.path().join("ブラシ系").join("fonts");
+        assert_eq!(
+            std::fs::read(fonts_dir.join("Arial.ttf")).unwrap(),
+            b"\x00\x01\x00\x00dummy"
+        );
+        assert_eq!(
+            std::fs::read(fonts_dir.join("Arial (2).ttf")).unwrap(),
+            b"\x00\x01\x00\x00other"
+        );
+        let css = std::fs::read_to_string(dir.path().join("ブラシ系").join("style.css")).unwrap();
+        assert!(css.contains(r#"src: url("fonts/Arial (2).ttf") format("truetype");"#));
+    }
+
+    #[test]
+    fn font_file_format_detects_signature() {
+        assert_eq!(font_file_format(b"OTTO"), ("otf", "opentype"));
+        assert_eq!(font_file_format(b"\x00\x01\x00\x00"), ("ttf", "truetype"));
+    }
+
+    #[test]
+    fn export_falls_back_to_local_for_broken_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut preset = preset();
+        preset.fonts[0].file = Some(FontFile::new(b"ttcf".to_vec(), 0));
+
+        export_html_specimen(dir.path(), &preset).unwrap();
+
+        let out_dir = dir.path().join("ブラシ系");
+        assert!(!out_dir.join("fonts").exists());
+        let css = std::fs::read_to_string(out_dir.join("style.css")).unwrap();
+        assert!(css.contains(r#"src: local("Arial");"#));
+    }
+}
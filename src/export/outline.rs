@@ -0,0 +1,97 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::text_metrics::ShapedLine;
+use rustybuzz::Face;
+use rustybuzz::ttf_parser::{OutlineBuilder, name_id};
+
+/// Path segments of a glyph outline in font units (y axis points up).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathSegment {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// Calls `f` with the origin (in font units, relative to the start of the baseline) and the
+/// outline of every glyph in `line`. Glyphs without an outline such as spaces are skipped.
+pub fn for_each_glyph_outline(
+    face: &Face,
+    line: &ShapedLine,
+    mut f: impl FnMut(i32, i32, &[PathSegment]),
+) {
+    let mut pen_x = 0;
+    for glyph in &line.glyphs {
+        let mut collector = SegmentCollector(vec![]);
+        if face.outline_glyph(glyph.glyph_id, &mut collector).is_some() {
+            f(pen_x + glyph.x_offset, glyph.y_offset, &collector.0);
+        }
+        pen_x += glyph.x_advance;
+    }
+}
+
+/// Returns a name from the `name` table, preferring the English entry.
+pub fn face_name(face: &Face, id: u16) -> Option<String> {
+    const LANGUAGE_ID_EN_US: u16 = 0x0409;
+
+    let names = face
+        .names()
+        .into_iter()
+        .filter(|name| name.name_id == id && name.is_unicode())
+        .collect::<Vec<_>>();
+    names
+        .iter()
+        .find(|name| name.language_id == LANGUAGE_ID_EN_US)
+        .or(names.first())
+        .and_then(|name| name.to_string())
+}
+
+/// One-line summary of the font metadata for specimens.
+pub fn face_metadata(face: &Face) -> String {
+    let family = face_name(face, name_id::FAMILY).unwrap_or_default();
+    let subfamily = face_name(face, name_id::SUBFAMILY).unwrap_or_default();
+    let version = face_name(face, name_id::VERSION).unwrap_or_default();
+    format!(
+        "{family} {subfamily} / weight {} / {} glyphs / {version}",
+        face.weight().to_number(),
+        face.number_of_glyphs(),
+    )
+}
+
+struct SegmentCollector(Vec<PathSegment>);
+
+impl OutlineBuilder for SegmentCollector {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.0.push(PathSegment::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.0.push(PathSegment::LineTo(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.0.push(PathSegment::QuadTo(x1, y1, x, y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.0.push(PathSegment::CurveTo(x1, y1, x2, y2, x, y));
+    }
+
+    fn close(&mut self) {
+        self.0.push(PathSegment::Close);
+    }
+}
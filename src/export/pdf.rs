@@ -0,0 +1,250 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::export::number;
+use crate::export::outline::PathSegment;
+use crate::prelude::*;
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use std::fmt::Write as _;
+use std::io::prelude::*;
+
+/// Minimal PDF 1.4 writer.
+///
+/// Text is drawn as filled glyph outlines, so the document does not embed or reference any font
+/// and renders the same on machines without Fonts66 installed.
+pub struct PdfWriter {
+    title: String,
+    width: f32,
+    height: f32,
+    pages: Vec<PageContent>,
+}
+
+impl PdfWriter {
+    pub fn new(title: &str, width: f32, height: f32) -> Self {
+        Self {
+            title: title.to_string(),
+            width,
+            height,
+            pages: vec![],
+        }
+    }
+
+    pub fn add_page(&mut self, page: PageContent) {
+        self.pages.push(page);
+    }
+
+    pub fn finish(self) -> Fallible<Vec<u8>> {
+        // Object layout: 1 = catalog, 2 = page tree, 3 = info, then a page object and its content
+        // stream for every page.
+        let page_id = |index: usize| 4 + index * 2;
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = vec![];
+
+        let mut write_object = |out: &mut Vec<u8>, body: &[u8]| {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", offsets.len()).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        };
+
+        write_object(&mut out, b"<< /Type /Catalog /Pages 2 0 R >>");
+
+        let kids = (0..self.pages.len())
+            .map(|index| format!("{} 0 R", page_id(index)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write_object(
+            &mut out,
+            format!(
+                "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+                self.pages.len()
+            )
+            .as_bytes(),
+        );
+
+        write_object(
+            &mut out,
+            format!(
+                "<< /Title {} /Producer {} >>",
+                text_string(&self.title),
+                text_string(concat!("Fonts66 Viewer ", env!("CARGO_PKG_VERSION"))),
+            )
+            .as_bytes(),
+        );
+
+        for (index, page) in self.pages.iter().enumerate() {
+            write_object(
+                &mut out,
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R /Resources << >> >>",
+                    number(self.width),
+                    number(self.height),
+                    page_id(index) + 1,
+                )
+                .as_bytes(),
+            );
+
+            let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+            encoder.write_all(page.ops.as_bytes())?;
+            let stream = encoder.finish()?;
+            let mut body = format!(
+                "<< /Length {} /Filter /FlateDecode >>\nstream\n",
+                stream.len()
+            )
+            .into_bytes();
+            body.extend_from_slice(&stream);
+            body.extend_from_slice(b"\nendstream");
+            write_object(&mut out, &body);
+        }
+
+        let xref_offset = out.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+        for offset in &offsets {
+            writeln!(xref, "{offset:010} 00000 n ")?;
+        }
+        write!(
+            xref,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            offsets.len() + 1,
+        )?;
+        out.extend_from_slice(xref.as_bytes());
+
+        Ok(out)
+    }
+}
+
+/// Content stream of a page. Coordinates are in points with the origin at the bottom-left.
+#[derive(Default)]
+pub struct PageContent {
+    ops: String,
+}
+
+impl PageContent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_fill_gray(&mut self, gray: f32) {
+        let _ = writeln!(self.ops, "{} g", number(gray));
+    }
+
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let _ = writeln!(
+            self.ops,
+            "{} {} {} {} re f",
+            number(x),
+            number(y),
+            number(width),
+            number(height),
+        );
+    }
+
+    /// Fills a glyph outline given in font units, placed with its origin at (`x`, `y`) and scaled
+    /// by `scale`.
+    pub fn fill_outline(&mut self, segments: &[PathSegment], x: f32, y: f32, scale: f32) {
+        let p = |px: f32, py: f32| format!("{} {}", number(x + px * scale), number(y + py * scale));
+
+        let mut current = (0.0, 0.0);
+        for segment in segments {
+            let _ = match *segment {
+                PathSegment::MoveTo(px, py) => {
+                    current = (px, py);
+                    writeln!(self.ops, "{} m", p(px, py))
+                }
+                PathSegment::LineTo(px, py) => {
+                    current = (px, py);
+                    writeln!(self.ops, "{} l", p(px, py))
+                }
+                PathSegment::QuadTo(x1, y1, px, py) => {
+                    // PDF has no quadratic curves; elevate to a cubic one.
+                    let (x0, y0) = current;
+                    let c1 = (x0 + 2.0 / 3.0 * (x1 - x0), y0 + 2.0 / 3.0 * (y1 - y0));
+                    let c2 = (px + 2.0 / 3.0 * (x1 - px), py + 2.0 / 3.0 * (y1 - py));
+                    current = (px, py);
+                    writeln!(
+                        self.ops,
+                        "{} {} {} c",
+                        p(c1.0, c1.1),
+                        p(c2.0, c2.1),
+                        p(px, py),
+                    )
+                }
+                PathSegment::CurveTo(x1, y1, x2, y2, px, py) => {
+                    current = (px, py);
+                    writeln!(self.ops, "{} {} {} c", p(x1, y1), p(x2, y2), p(px, py))
+                }
+                PathSegment::Close => writeln!(self.ops, "h"),
+            };
+        }
+        self.ops.push_str("f\n");
+    }
+}
+
+/// Encodes `value` as a UTF-16BE hex string so that Japanese preset names survive.
+fn text_string(value: &str) -> String {
+    let mut s = "<FEFF".to_string();
+    for unit in value.encode_utf16() {
+        let _ = write!(s, "{unit:04X}");
+    }
+    s.push('>');
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finish_writes_header_pages_and_trailer() {
+        let mut writer = PdfWriter::new("Preset 1", 595.0, 842.0);
+        writer.add_page(PageContent::new());
+        writer.add_page(PageContent::new());
+        let data = writer.finish().unwrap();
+        let text = String::from_utf8_lossy(&data);
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("/Kids [4 0 R 6 0 R]"));
+    }
+
+    #[test]
+    fn xref_offsets_point_to_objects() {
+        let mut writer = PdfWriter::new("test", 100.0, 100.0);
+        writer.add_page(PageContent::new());
+        let data = writer.finish().unwrap();
+        let text = String::from_utf8_lossy(&data);
+
+        let xref = text.rfind("\nxref\n").unwrap() + 1;
+        let entries = text[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 5);
+        for (index, offset) in entries.into_iter().enumerate() {
+            assert!(data[offset..].starts_with(format!("{} 0 obj", index + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn text_string_encodes_utf16() {
+        assert_eq!(text_string("A"), "<FEFF0041>");
+        assert_eq!(text_string("桜"), "<FEFF685C>");
+    }
+}
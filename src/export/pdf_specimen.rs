@@ -0,0 +1,302 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::export::outline::{face_metadata, for_each_glyph_outline};
+use crate::export::pdf::{PageContent, PdfWriter};
+use crate::export::{PresetExport, unique_path, write_file};
+use crate::model::{FontEntry, FontFile};
+use crate::prelude::*;
+use crate::text_metrics::{ShapedLine, shape_wrapped};
+use rustybuzz::Face;
+use std::path::{Path, PathBuf};
+
+// A4 in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const PAGE_MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - PAGE_MARGIN * 2.0;
+
+const WATERFALL_SIZES: &[f32] = &[9.0, 12.0, 18.0, 24.0, 36.0, 48.0, 72.0];
+const SAMPLE_ROWS: &[(&str, &str)] = &[
+    (
+        "Kana",
+        "あいうえおかきくけこさしすせそたちつてとなにぬねの アイウエオカキクケコサシスセソタチツテト",
+    ),
+    (
+        "Kanji",
+        "永東国酬鬱愛霊驚薔薇檸檬 春夏秋冬 花鳥風月 一二三四五六七八九十",
+    ),
+    (
+        "Latin",
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ abcdefghijklmnopqrstuvwxyz 0123456789",
+    ),
+];
+
+const LABEL_GRAY: f32 = 0.45;
+const RULE_GRAY: f32 = 0.8;
+
+/// Writes a specimen book of `preset` to `dir` and returns the path of the written file.
+pub fn export_pdf_specimen(
+    dir: &Path,
+    preset: &PresetExport,
+    label_font: Option<&FontFile>,
+) -> Fallible<PathBuf> {
+    let data = render_pdf_specimen(preset, label_font)?;
+    let path = unique_path(dir, &preset.name, "pdf");
+    write_file(&path, &data)?;
+    info!(path = %path.display(), "exported pdf specimen");
+    Ok(path)
+}
+
+fn render_pdf_specimen(preset: &PresetExport, label_font: Option<&FontFile>) -> Fallible<Vec<u8>> {
+    let PresetExport {
+        name: preset_name,
+        fonts,
+        custom_text,
+        ..
+    } = preset;
+    let label_face = label_font.and_then(|file| Face::from_slice(&file.data, file.index));
+    let mut layout = Layout::new(preset_name, label_face);
+
+    // Cover page.
+    layout.advance(PAGE_HEIGHT / 4.0);
+    layout.label(preset_name, 36.0, None);
+    layout.advance(12.0);
+    layout.set_gray(LABEL_GRAY);
+    layout.label(
+        &format!("Fonts66 specimen / {} fonts", fonts.len()),
+        14.0,
+        None,
+    );
+    layout.advance(24.0);
+    for font in fonts {
+        layout.label(&font_title(&font.entry), 10.0, None);
+    }
+    layout.set_gray(0.0);
+
+    for font in fonts {
+        layout.new_page();
+
+        let face = font
+            .file
+            .as_ref()
+            .and_then(|file| Face::from_slice(&file.data, file.index));
+
+        layout.label(&font_title(&font.entry), 20.0, face.as_ref());
+        layout.set_gray(LABEL_GRAY);
+        layout.label(&font.entry.filepath, 9.0, face.as_ref());
+        match &face {
+            Some(face) => layout.label(&face_metadata(face), 9.0, None),
+            None => layout.label("Font file is not found on this system.", 9.0, None),
+        }
+        layout.set_gray(0.0);
+        layout.rule();
+
+        let Some(face) = face else {
+            continue;
+        };
+
+        let first_line = custom_text.lines().next().unwrap_or_default();
+        for size in WATERFALL_SIZES {
+            layout.set_gray(LABEL_GRAY);
+            layout.label(&format!("{size}pt"), 7.0, None);
+            layout.set_gray(0.0);
+            layout.text(&face, first_line, *size, false);
+            layout.advance(4.0);
+        }
+        layout.rule();
+
+        for (name, sample) in SAMPLE_ROWS {
+            layout.set_gray(LABEL_GRAY);
+            layout.label(name, 7.0, None);
+            layout.set_gray(0.0);
+            layout.text(&face, sample, 18.0, true);
+            layout.advance(6.0);
+        }
+        layout.rule();
+
+        layout.set_gray(LABEL_GRAY);
+        layout.label("Custom text", 7.0, None);
+        layout.set_gray(0.0);
+        layout.text(&face, custom_text, 24.0, true);
+    }
+
+    layout.finish()
+}
+
+fn font_title(entry: &FontEntry) -> String {
+    match &entry.display_name {
+        Some(display_name) => format!("{display_name} ({})", entry.font_name),
+        None => entry.font_name.to_string(),
+    }
+}
+
+/// Flows lines of text from top to bottom, starting a new page when the current one is full.
+struct Layout<'a> {
+    writer: PdfWriter,
+    page: PageContent,
+    y: f32,
+    label_face: Option<Face<'a>>,
+}
+
+impl<'a> Layout<'a> {
+    fn new(title: &str, label_face: Option<Face<'a>>) -> Self {
+        Self {
+            writer: PdfWriter::new(title, PAGE_WIDTH, PAGE_HEIGHT),
+            page: PageContent::new(),
+            y: PAGE_HEIGHT - PAGE_MARGIN,
+            label_face,
+        }
+    }
+
+    fn new_page(&mut self) {
+        let page = std::mem::take(&mut self.page);
+        self.writer.add_page(page);
+        self.y = PAGE_HEIGHT - PAGE_MARGIN;
+    }
+
+    fn advance(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < PAGE_MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn set_gray(&mut self, gray: f32) {
+        self.page.set_fill_gray(gray);
+    }
+
+    fn rule(&mut self) {
+        self.reserve(17.0);
+        self.advance(8.0);
+        self.page.set_fill_gray(RULE_GRAY);
+        self.page.fill_rect(PAGE_MARGIN, self.y, CONTENT_WIDTH, 0.5);
+        self.page.set_fill_gray(0.0);
+        self.advance(9.0);
+    }
+
+    /// Draws a label with the label font, falling back to `fallback` when no label font is
+    /// available. If neither is available only the space is reserved.
+    fn label(&mut self, text: &str, size: f32, fallback: Option<&Face>) {
+        match self.label_face.take() {
+            Some(face) => {
+                self.text(&face, text, size, true);
+                self.label_face = Some(face);
+            }
+            None => match fallback {
+                Some(face) => self.text(face, text, size, true),
+                None => self.advance(size * 1.4),
+            },
+        }
+    }
+
+    /// Draws `text` with `face`. If `wrap` is false only the part that fits on the first line is
+    /// drawn.
+    fn text(&mut self, face: &Face, text: &str, size: f32, wrap: bool) {
+        let scale = size / face.units_per_em() as f32;
+        let ascent = face.ascender() as f32 * scale;
+        let descent = -(face.descender() as f32) * scale;
+        let max_width = (CONTENT_WIDTH / scale) as i32;
+
+        let mut lines = shape_wrapped(face, text, max_width);
+        if !wrap {
+            lines.truncate(1);
+        }
+
+        for line in &lines {
+            self.reserve(ascent + descent);
+            self.advance(ascent);
+            self.draw_line(face, line, scale);
+            self.advance(descent + size * 0.2);
+        }
+    }
+
+    fn draw_line(&mut self, face: &Face, line: &ShapedLine, scale: f32) {
+        let (x, y) = (PAGE_MARGIN, self.y);
+        let page = &mut self.page;
+        for_each_glyph_outline(face, line, |glyph_x, glyph_y, segments| {
+            page.fill_outline(
+                segments,
+                x + glyph_x as f32 * scale,
+                y + glyph_y as f32 * scale,
+                scale,
+            );
+        });
+    }
+
+    fn finish(mut self) -> Fallible<Vec<u8>> {
+        let page = std::mem::take(&mut self.page);
+        self.writer.add_page(page);
+        self.writer.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::SpecimenFont;
+
+    #[test]
+    fn render_creates_cover_and_page_per_font() {
+        let fonts = vec![
+            SpecimenFont {
+                entry: FontEntry::new("Arial".into(), "./arial.ttf".into(), None, "Arial"),
+                file: None,
+            },
+            SpecimenFont {
+                entry: FontEntry::new(
+                    "Times New Roman".into(),
+                    "./times.ttf".into(),
+                    None,
+                    "Times New Roman",
+                ),
+                file: None,
+            },
+        ];
+
+        let preset = PresetExport {
+            name: "Preset 1".into(),
+            font_size: 24,
+            fonts,
+            custom_text: "sample".into(),
+        };
+
+        let data = render_pdf_specimen(&preset, None).unwrap();
+        let text = String::from_utf8_lossy(&data);
+
+        assert!(text.contains("/Count 3"));
+    }
+
+    #[test]
+    fn export_writes_unique_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let preset = PresetExport {
+            name: "Preset 1".into(),
+            font_size: 24,
+            fonts: vec![],
+            custom_text: "".into(),
+        };
+
+        let first = export_pdf_specimen(dir.path(), &preset, None).unwrap();
+        let second = export_pdf_specimen(dir.path(), &preset, None).unwrap();
+
+        assert_eq!(first, dir.path().join("Preset 1.pdf"));
+        assert_eq!(second, dir.path().join("Preset 1 (2).pdf"));
+        assert!(std::fs::read(first).unwrap().starts_with(b"%PDF-"));
+    }
+}
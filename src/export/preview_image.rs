@@ -0,0 +1,128 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::export::outline::{PathSegment, for_each_glyph_outline};
+use crate::model::{FontFile, PreviewImage};
+use crate::prelude::*;
+use crate::text_metrics::shape_line;
+use rustybuzz::Face;
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Transform};
+
+/// Margin around the text in pixels, so that the image does not look cropped when pasted.
+const MARGIN: f32 = 8.0;
+
+/// Renders `text` set in `file` to a bitmap with black text on a white background, the same as
+/// a list item of the main view.
+pub fn render_preview_image(file: &FontFile, text: &str, font_size: f32) -> Fallible<PreviewImage> {
+    let face = Face::from_slice(&file.data, file.index).context("failed to parse font file")?;
+    let scale = font_size / face.units_per_em() as f32;
+    let ascent = face.ascender() as f32 * scale;
+    let line_height = (face.ascender() - face.descender() + face.line_gap()) as f32 * scale;
+
+    let lines = text
+        .lines()
+        .map(|line| shape_line(&face, line))
+        .collect::<Vec<_>>();
+    let width = lines.iter().map(|line| line.width).max().unwrap_or(0) as f32 * scale;
+    let height = line_height * lines.len() as f32;
+
+    let mut pixmap = Pixmap::new(
+        (width + MARGIN * 2.0).ceil() as u32,
+        (height + MARGIN * 2.0).ceil() as u32,
+    )
+    .context("preview image is too large")?;
+    pixmap.fill(Color::WHITE);
+
+    let mut builder = PathBuilder::new();
+    for (index, line) in lines.iter().enumerate() {
+        let baseline = MARGIN + ascent + line_height * index as f32;
+        for_each_glyph_outline(&face, line, |glyph_x, glyph_y, segments| {
+            let x = MARGIN + glyph_x as f32 * scale;
+            let y = baseline - glyph_y as f32 * scale;
+            append_path(&mut builder, segments, x, y, scale);
+        });
+    }
+
+    if let Some(path) = builder.finish() {
+        let mut paint = Paint::default();
+        paint.set_color(Color::BLACK);
+        paint.anti_alias = true;
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    // The background is opaque, so the premultiplied pixels are the same as straight RGBA.
+    Ok(PreviewImage {
+        width: pixmap.width(),
+        height: pixmap.height(),
+        rgba: pixmap.take(),
+    })
+}
+
+/// Appends `segments` given in font units. The y axis is flipped since pixel rows go down.
+fn append_path(builder: &mut PathBuilder, segments: &[PathSegment], x: f32, y: f32, scale: f32) {
+    let px = |value: f32| x + value * scale;
+    let py = |value: f32| y - value * scale;
+    for segment in segments {
+        match *segment {
+            PathSegment::MoveTo(x0, y0) => builder.move_to(px(x0), py(y0)),
+            PathSegment::LineTo(x0, y0) => builder.line_to(px(x0), py(y0)),
+            PathSegment::QuadTo(x1, y1, x0, y0) => builder.quad_to(px(x1), py(y1), px(x0), py(y0)),
+            PathSegment::CurveTo(x1, y1, x2, y2, x0, y0) => {
+                builder.cubic_to(px(x1), py(y1), px(x2), py(y2), px(x0), py(y0))
+            }
+            PathSegment::Close => builder.close(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_path_flips_y_axis() {
+        let mut builder = PathBuilder::new();
+        append_path(
+            &mut builder,
+            &[
+                PathSegment::MoveTo(0.0, 0.0),
+                PathSegment::LineTo(100.0, 100.0),
+                PathSegment::LineTo(100.0, 0.0),
+                PathSegment::Close,
+            ],
+            10.0,
+            50.0,
+            0.1,
+        );
+
+        let bounds = builder.finish().unwrap().bounds();
+        assert_eq!(
+            (bounds.left(), bounds.top(), bounds.right(), bounds.bottom()),
+            (10.0, 40.0, 20.0, 50.0),
+        );
+    }
+
+    #[test]
+    fn render_preview_image_rejects_invalid_font() {
+        let file = FontFile::new(vec![0; 16], 0);
+        assert!(render_preview_image(&file, "text", 28.0).is_err());
+    }
+}
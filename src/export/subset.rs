@@ -0,0 +1,729 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::export::{PresetExport, unique_dir, unique_path, write_file};
+use crate::model::ExportResult;
+use crate::prelude::*;
+use crate::text_metrics::shape_line;
+use brotli::enc::BrotliEncoderParams;
+use brotli::enc::backward_references::BrotliEncoderMode;
+use rustybuzz::Face;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+const TAG_CMAP: [u8; 4] = *b"cmap";
+const TAG_DSIG: [u8; 4] = *b"DSIG";
+const TAG_GLYF: [u8; 4] = *b"glyf";
+const TAG_HEAD: [u8; 4] = *b"head";
+const TAG_LOCA: [u8; 4] = *b"loca";
+const TAG_MAXP: [u8; 4] = *b"maxp";
+
+const HEAD_CHECKSUM_ADJUSTMENT_OFFSET: usize = 8;
+const HEAD_INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
+
+/// Writes a subset TTF and WOFF2 of every font in `preset` containing only the glyphs of `text`
+/// into a new folder in `dir`. The summary reports the size saved compared to the original
+/// files.
+///
+/// Fonts that could not be subset or converted are skipped and counted in the summary.
+pub fn export_font_subsets(
+    dir: &Path,
+    preset: &PresetExport,
+    text: &str,
+) -> Fallible<ExportResult> {
+    if text.chars().all(char::is_whitespace) {
+        bail!("no characters to subset");
+    }
+
+    let out_dir = unique_dir(dir, &format!("{} subset", preset.name));
+    let mut original_size = 0;
+    let mut subset_size = 0;
+    let mut skipped = 0;
+    for font in &preset.fonts {
+        let font_name = font.entry.font_name;
+        let Some(file) = &font.file else {
+            warn!(font_name, "font file is not found");
+            skipped += 1;
+            continue;
+        };
+
+        let ttf = match subset_font(&file.data, file.index, text) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(?e, font_name, "failed to subset font");
+                skipped += 1;
+                continue;
+            }
+        };
+        let woff2 = match to_woff2(&ttf) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(?e, font_name, "failed to convert font to woff2");
+                skipped += 1;
+                continue;
+            }
+        };
+
+        let ttf_path = unique_path(&out_dir, font_name, "ttf");
+        write_file(&ttf_path, &ttf)?;
+        write_file(&ttf_path.with_extension("woff2"), &woff2)?;
+        original_size += face_size(&file.data, file.index);
+        subset_size += ttf.len();
+    }
+
+    if original_size == 0 {
+        bail!("no fonts could be subset");
+    }
+
+    let mut summary = format!(
+        "{} to {}, saved {}",
+        format_size(original_size),
+        format_size(subset_size),
+        format_size(original_size.saturating_sub(subset_size)),
+    );
+    if skipped > 0 {
+        summary.push_str(&format!(", skipped {skipped} fonts"));
+    }
+    info!(path = %out_dir.display(), summary, "exported font subsets");
+
+    Ok(ExportResult {
+        path: out_dir,
+        summary: Some(summary),
+    })
+}
+
+/// Returns the size of the face at `index`, so that a face of a collection is compared with its
+/// subset rather than the whole collection.
+fn face_size(data: &[u8], index: u32) -> usize {
+    extract_face(data, index).map_or(data.len(), |face| face.len())
+}
+
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+
+    let bytes = bytes as f64;
+    if bytes >= MB {
+        format!("{:.1} MB", bytes / MB)
+    } else {
+        format!("{:.1} KB", bytes / KB)
+    }
+}
+
+/// Subsets a TrueType font to the glyphs needed to render `text`.
+///
+/// Glyph IDs are kept as is and the outlines of unused glyphs are emptied, so tables that refer
+/// to glyph IDs such as `hmtx` and `GSUB` stay valid without being rewritten. `cmap` is rebuilt
+/// to contain only the characters of `text`, and `DSIG` is dropped since the signature no longer
+/// matches. CFF-based fonts are not supported.
+pub fn subset_font(data: &[u8], index: u32, text: &str) -> Fallible<Vec<u8>> {
+    let tables = read_tables(data, index)?;
+    if read_u32(data, face_offset(data, index)?)? == u32::from_be_bytes(*b"OTTO") {
+        bail!("CFF-based fonts are not supported");
+    }
+    let table = |tag: &[u8; 4]| {
+        tables
+            .get(tag)
+            .copied()
+            .with_context(|| format!("{} table is not found", String::from_utf8_lossy(tag)))
+    };
+
+    if !tables.contains_key(&TAG_GLYF) {
+        bail!("only TrueType outlines are supported");
+    }
+
+    let head = table(&TAG_HEAD)?;
+    let maxp = table(&TAG_MAXP)?;
+    let num_glyphs = read_u16(maxp, 4)? as usize;
+    let long_loca = read_u16(head, HEAD_INDEX_TO_LOC_FORMAT_OFFSET)? != 0;
+    let loca = read_loca(table(&TAG_LOCA)?, num_glyphs, long_loca)?;
+    let glyf = table(&TAG_GLYF)?;
+
+    let face = Face::from_slice(data, index).context("failed to parse font")?;
+    let mut cmap = BTreeMap::new();
+    let mut glyphs = BTreeSet::from([0u16]);
+    for c in text.chars() {
+        if let Some(glyph_id) = face.glyph_index(c) {
+            cmap.insert(c as u32, glyph_id.0);
+            glyphs.insert(glyph_id.0);
+        }
+    }
+    // Glyphs substituted by the shaper such as ligatures are not reachable from cmap.
+    for line in text.lines() {
+        glyphs.extend(shape_line(&face, line).glyphs.iter().map(|g| g.glyph_id.0));
+    }
+    add_composite_components(&mut glyphs, glyf, &loca)?;
+
+    let mut new_glyf = vec![];
+    let mut new_loca = Vec::with_capacity((num_glyphs + 1) * 4);
+    for glyph_id in 0..num_glyphs {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if glyphs.contains(&(glyph_id as u16)) {
+            new_glyf.extend_from_slice(glyph_data(glyf, &loca, glyph_id)?);
+            pad4(&mut new_glyf);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    let mut new_head = head.to_vec();
+    new_head
+        .get_mut(HEAD_INDEX_TO_LOC_FORMAT_OFFSET..HEAD_INDEX_TO_LOC_FORMAT_OFFSET + 2)
+        .context("head table is too short")?
+        .copy_from_slice(&1u16.to_be_bytes());
+
+    let mut out_tables = BTreeMap::new();
+    for (tag, data) in &tables {
+        let data = match *tag {
+            TAG_DSIG => continue,
+            TAG_CMAP => build_cmap(&cmap)?,
+            TAG_GLYF => std::mem::take(&mut new_glyf),
+            TAG_HEAD => std::mem::take(&mut new_head),
+            TAG_LOCA => std::mem::take(&mut new_loca),
+            _ => data.to_vec(),
+        };
+        out_tables.insert(*tag, data);
+    }
+
+    Ok(write_sfnt(0x00010000, &out_tables))
+}
+
+/// Extracts the face at `index` of a TrueType Collection as a standalone font. Fonts other than
+/// collections are returned as is.
+pub fn extract_face(data: &[u8], index: u32) -> Fallible<Vec<u8>> {
+    if data.get(..4) != Some(b"ttcf") {
+        return Ok(data.to_vec());
+    }
+
+    let flavor = read_u32(data, face_offset(data, index)?)?;
+    let tables = read_tables(data, index)?
+        .into_iter()
+        .map(|(tag, data)| (tag, data.to_vec()))
+        .collect();
+    Ok(write_sfnt(flavor, &tables))
+}
+
+/// Wraps a font in WOFF2 with every table Brotli-compressed as a single stream.
+///
+/// Tables are stored with the null transform, so `glyf` and `loca` are not rewritten into the
+/// WOFF2 glyph format. The file is a little larger than a fully transformed one but is decoded
+/// by every WOFF2 implementation.
+pub fn to_woff2(data: &[u8]) -> Fallible<Vec<u8>> {
+    let tables = read_tables(data, 0)?;
+    let flavor = read_u32(data, 0)?;
+
+    let mut directory = vec![];
+    let mut table_data = vec![];
+    let mut total_sfnt_size = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        // The null transform is version 3 for glyf and loca and version 0 for other tables.
+        let transform = if *tag == TAG_GLYF || *tag == TAG_LOCA {
+            3
+        } else {
+            0
+        };
+        // Tag index 63 means the tag follows instead of an index into the known tags.
+        directory.push(transform << 6 | 63);
+        directory.extend_from_slice(tag);
+        write_uint_base128(&mut directory, data.len() as u32);
+        table_data.extend_from_slice(data);
+        total_sfnt_size += data.len().next_multiple_of(4);
+    }
+
+    let params = BrotliEncoderParams {
+        mode: BrotliEncoderMode::BROTLI_MODE_FONT,
+        size_hint: table_data.len(),
+        ..Default::default()
+    };
+    let mut compressed = vec![];
+    brotli::BrotliCompress(&mut &table_data[..], &mut compressed, &params)?;
+
+    let header_len = 48 + directory.len();
+    let length = (header_len + compressed.len()).next_multiple_of(4);
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(b"wOF2");
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&(length as u32).to_be_bytes());
+    out.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&(total_sfnt_size as u32).to_be_bytes());
+    out.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    // metaOffset, metaLength, metaOrigLength, privOffset and privLength.
+    out.extend_from_slice(&[0; 20]);
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(&compressed);
+    pad4(&mut out);
+    Ok(out)
+}
+
+/// Appends `value` as a WOFF2 UIntBase128: big-endian groups of 7 bits with the high bit set on
+/// every byte except the last.
+fn write_uint_base128(out: &mut Vec<u8>, value: u32) {
+    let len = (1..5).find(|i| value >> (7 * i) == 0).unwrap_or(5);
+    for i in (0..len).rev() {
+        let byte = (value >> (7 * i)) as u8 & 0x7F;
+        out.push(if i == 0 { byte } else { byte | 0x80 });
+    }
+}
+
+/// Returns the tables of the face at `index`, following the TTC header if there is one.
+fn read_tables(data: &[u8], index: u32) -> Fallible<BTreeMap<[u8; 4], &[u8]>> {
+    let offset = face_offset(data, index)?;
+    let num_tables = read_u16(data, offset + 4)? as usize;
+    let mut tables = BTreeMap::new();
+    for i in 0..num_tables {
+        let record = offset + 12 + i * 16;
+        let tag = data
+            .get(record..record + 4)
+            .context("table record is out of range")?;
+        let table_offset = read_u32(data, record + 8)? as usize;
+        let table_len = read_u32(data, record + 12)? as usize;
+        let table = data
+            .get(table_offset..table_offset + table_len)
+            .context("table is out of range")?;
+        tables.insert(tag.try_into()?, table);
+    }
+    Ok(tables)
+}
+
+fn read_loca(loca: &[u8], num_glyphs: usize, long: bool) -> Fallible<Vec<usize>> {
+    (0..=num_glyphs)
+        .map(|i| {
+            Ok(if long {
+                read_u32(loca, i * 4)? as usize
+            } else {
+                read_u16(loca, i * 2)? as usize * 2
+            })
+        })
+        .collect()
+}
+
+fn glyph_data<'a>(glyf: &'a [u8], loca: &[usize], glyph_id: usize) -> Fallible<&'a [u8]> {
+    let start = loca[glyph_id];
+    let end = loca[glyph_id + 1];
+    glyf.get(start..end.max(start))
+        .with_context(|| format!("glyph {glyph_id} is out of range"))
+}
+
+/// Adds the glyphs referenced by composite glyphs in `glyphs`, recursively.
+fn add_composite_components(
+    glyphs: &mut BTreeSet<u16>,
+    glyf: &[u8],
+    loca: &[usize],
+) -> Fallible<()> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    let mut pending = glyphs.iter().copied().collect::<Vec<_>>();
+    while let Some(glyph_id) = pending.pop() {
+        if glyph_id as usize + 1 >= loca.len() {
+            continue;
+        }
+        let data = glyph_data(glyf, loca, glyph_id as usize)?;
+        if data.len() < 10 || (read_u16(data, 0)? as i16) >= 0 {
+            continue;
+        }
+
+        let mut offset = 10;
+        loop {
+            let flags = read_u16(data, offset)?;
+            let component = read_u16(data, offset + 2)?;
+            if glyphs.insert(component) {
+                pending.push(component);
+            }
+
+            offset += 4;
+            offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+                4
+            } else {
+                2
+            };
+            if flags & WE_HAVE_A_SCALE != 0 {
+                offset += 2;
+            } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                offset += 4;
+            } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                offset += 8;
+            }
+            if flags & MORE_COMPONENTS == 0 {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds a `cmap` table with a format 4 subtable for the BMP and, if needed, a format 12
+/// subtable for the supplementary planes.
+fn build_cmap(cmap: &BTreeMap<u32, u16>) -> Fallible<Vec<u8>> {
+    let mut subtables = vec![((3u16, 1u16), build_cmap_format4(cmap)?)];
+    if cmap.keys().any(|&c| c > 0xFFFF) {
+        subtables.push(((3, 10), build_cmap_format12(cmap)));
+    }
+
+    let mut out = vec![];
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&(subtables.len() as u16).to_be_bytes());
+    let mut offset = 4 + 8 * subtables.len();
+    for ((platform_id, encoding_id), subtable) in &subtables {
+        out.extend_from_slice(&platform_id.to_be_bytes());
+        out.extend_from_slice(&encoding_id.to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        offset += subtable.len();
+    }
+    for (_, subtable) in subtables {
+        out.extend_from_slice(&subtable);
+    }
+    Ok(out)
+}
+
+fn build_cmap_format4(cmap: &BTreeMap<u32, u16>) -> Fallible<Vec<u8>> {
+    // Runs of consecutive characters mapped to consecutive glyphs share a segment.
+    let mut segments: Vec<(u16, u16, u16)> = vec![];
+    for (&c, &glyph_id) in cmap.range(..0xFFFF) {
+        let c = c as u16;
+        match segments.last_mut() {
+            Some((start, end, start_glyph))
+                if *end + 1 == c && start_glyph.wrapping_add(c - *start) == glyph_id =>
+            {
+                *end = c;
+            }
+            _ => segments.push((c, c, glyph_id)),
+        }
+    }
+    // The last segment must map 0xFFFF to .notdef.
+    segments.push((0xFFFF, 0xFFFF, 0));
+
+    let seg_count = segments.len();
+    let length = 16 + seg_count * 8;
+    if length > u16::MAX as usize {
+        bail!("too many characters to subset");
+    }
+    let entry_selector = seg_count.ilog2() as u16;
+    let search_range = 2 * (1u16 << entry_selector);
+
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(&4u16.to_be_bytes());
+    out.extend_from_slice(&(length as u16).to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&((seg_count * 2) as u16).to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&((seg_count * 2) as u16 - search_range).to_be_bytes());
+    for (_, end, _) in &segments {
+        out.extend_from_slice(&end.to_be_bytes());
+    }
+    out.extend_from_slice(&0u16.to_be_bytes());
+    for (start, _, _) in &segments {
+        out.extend_from_slice(&start.to_be_bytes());
+    }
+    for (start, _, start_glyph) in &segments {
+        let delta = if *start == 0xFFFF {
+            1
+        } else {
+            start_glyph.wrapping_sub(*start)
+        };
+        out.extend_from_slice(&delta.to_be_bytes());
+    }
+    for _ in &segments {
+        out.extend_from_slice(&0u16.to_be_bytes());
+    }
+    Ok(out)
+}
+
+fn build_cmap_format12(cmap: &BTreeMap<u32, u16>) -> Vec<u8> {
+    let mut groups: Vec<(u32, u32, u32)> = vec![];
+    for (&c, &glyph_id) in cmap {
+        let glyph_id = glyph_id as u32;
+        match groups.last_mut() {
+            Some((start, end, start_glyph))
+                if *end + 1 == c && *start_glyph + (c - *start) == glyph_id =>
+            {
+                *end = c;
+            }
+            _ => groups.push((c, c, glyph_id)),
+        }
+    }
+
+    let mut out = Vec::with_capacity(16 + groups.len() * 12);
+    out.extend_from_slice(&12u16.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&((16 + groups.len() * 12) as u32).to_be_bytes());
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(&(groups.len() as u32).to_be_bytes());
+    for (start, end, start_glyph) in groups {
+        out.extend_from_slice(&start.to_be_bytes());
+        out.extend_from_slice(&end.to_be_bytes());
+        out.extend_from_slice(&start_glyph.to_be_bytes());
+    }
+    out
+}
+
+/// Returns the offset of the table directory of the face at `index`.
+fn face_offset(data: &[u8], index: u32) -> Fallible<usize> {
+    if data.get(..4) != Some(b"ttcf") {
+        return Ok(0);
+    }
+
+    let num_fonts = read_u32(data, 8)?;
+    if index >= num_fonts {
+        bail!("face {index} is out of range of {num_fonts} faces");
+    }
+    Ok(read_u32(data, 12 + index as usize * 4)? as usize)
+}
+
+/// Serializes `tables` as a font of `flavor` and fixes up the checksums.
+fn write_sfnt(flavor: u32, tables: &BTreeMap<[u8; 4], Vec<u8>>) -> Vec<u8> {
+    let num_tables = tables.len();
+    let entry_selector = num_tables.max(1).ilog2() as u16;
+    let search_range = 16 * (1u16 << entry_selector);
+
+    let mut out = vec![];
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&(num_tables as u16).to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&((num_tables * 16) as u16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + 16 * num_tables;
+    let mut head_offset = None;
+    for (tag, data) in tables {
+        let mut data_checksum = checksum(data);
+        if *tag == TAG_HEAD {
+            head_offset = Some(offset);
+            // checksum of head is calculated with checkSumAdjustment set to 0.
+            let mut head = data.clone();
+            if let Some(adjustment) =
+                head.get_mut(HEAD_CHECKSUM_ADJUSTMENT_OFFSET..HEAD_CHECKSUM_ADJUSTMENT_OFFSET + 4)
+            {
+                adjustment.fill(0);
+            }
+            data_checksum = checksum(&head);
+        }
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&data_checksum.to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+
+    for data in tables.values() {
+        out.extend_from_slice(data);
+        pad4(&mut out);
+    }
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = head_offset + HEAD_CHECKSUM_ADJUSTMENT_OFFSET;
+        if out.len() >= adjustment + 4 {
+            out[adjustment..adjustment + 4].fill(0);
+            let value = 0xB1B0AFBAu32.wrapping_sub(checksum(&out));
+            out[adjustment..adjustment + 4].copy_from_slice(&value.to_be_bytes());
+        }
+    }
+    out
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn pad4(data: &mut Vec<u8>) {
+    data.resize(data.len().next_multiple_of(4), 0);
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Fallible<u16> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .context("unexpected end of data")?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Fallible<u32> {
+    let bytes = data
+        .get(offset..offset + 4)
+        .context("unexpected end of data")?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_cmap_format4_merges_consecutive_characters() {
+        let cmap = BTreeMap::from([(0x41, 10), (0x42, 11), (0x44, 20), (0x3042, 300)]);
+
+        let data = build_cmap_format4(&cmap).unwrap();
+
+        // 3 segments + the terminating 0xFFFF segment.
+        assert_eq!(read_u16(&data, 6).unwrap(), 8);
+        let end_codes = (0..4)
+            .map(|i| read_u16(&data, 14 + i * 2).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(end_codes, vec![0x42, 0x44, 0x3042, 0xFFFF]);
+        let start_codes = (0..4)
+            .map(|i| read_u16(&data, 16 + 8 + i * 2).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(start_codes, vec![0x41, 0x44, 0x3042, 0xFFFF]);
+        let id_delta = read_u16(&data, 16 + 16).unwrap();
+        assert_eq!(0x41u16.wrapping_add(id_delta), 10);
+    }
+
+    #[test]
+    fn build_cmap_adds_format12_for_supplementary_planes() {
+        let cmap = BTreeMap::from([(0x41, 1), (0x20B9F, 2)]);
+
+        let data = build_cmap(&cmap).unwrap();
+
+        assert_eq!(read_u16(&data, 2).unwrap(), 2);
+        assert_eq!(read_u16(&data, 14).unwrap(), 10);
+    }
+
+    #[test]
+    fn write_sfnt_fixes_checksum_adjustment() {
+        let mut head = vec![0u8; 54];
+        head[HEAD_INDEX_TO_LOC_FORMAT_OFFSET + 1] = 1;
+        let tables = BTreeMap::from([(TAG_HEAD, head), (*b"test", vec![1, 2, 3])]);
+
+        let data = write_sfnt(0x00010000, &tables);
+
+        assert_eq!(checksum(&data), 0xB1B0AFBA);
+        let read = read_tables(&data, 0).unwrap();
+        assert_eq!(read[b"test"], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn to_woff2_compresses_table_data() {
+        let tables = BTreeMap::from([(TAG_GLYF, vec![0u8; 1000]), (*b"tiny", vec![1])]);
+        let sfnt = write_sfnt(0x00010000, &tables);
+
+        let woff2 = to_woff2(&sfnt).unwrap();
+
+        assert_eq!(&woff2[..4], b"wOF2");
+        assert_eq!(read_u32(&woff2, 8).unwrap() as usize, woff2.len());
+        assert_eq!(woff2.len() % 4, 0);
+        assert_eq!(read_u16(&woff2, 12).unwrap(), 2);
+        assert_eq!(read_u32(&woff2, 16).unwrap() as usize, sfnt.len());
+        // glyf with the null transform, 1000 as UIntBase128, then tiny.
+        assert_eq!(
+            &woff2[48..60],
+            &[
+                0xFF, b'g', b'l', b'y', b'f', 0x87, 0x68, 0x3F, b't', b'i', b'n', b'y'
+            ]
+        );
+        assert_eq!(woff2[60], 1);
+
+        let compressed_len = read_u32(&woff2, 20).unwrap() as usize;
+        let mut decompressed = vec![];
+        brotli::BrotliDecompress(&mut &woff2[61..61 + compressed_len], &mut decompressed).unwrap();
+        let mut expected = vec![0u8; 1000];
+        expected.push(1);
+        assert_eq!(decompressed, expected);
+    }
+
+    #[test]
+    fn write_uint_base128_uses_minimum_bytes() {
+        let encode = |value| {
+            let mut out = vec![];
+            write_uint_base128(&mut out, value);
+            out
+        };
+
+        assert_eq!(encode(0), vec![0]);
+        assert_eq!(encode(127), vec![0x7F]);
+        assert_eq!(encode(128), vec![0x81, 0x00]);
+        assert_eq!(encode(u32::MAX), vec![0x8F, 0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    fn build_ttc(faces: &[&[u8]]) -> Vec<u8> {
+        // Rebase the table offsets of each face onto the collection.
+        let mut ttc = b"ttcf".to_vec();
+        ttc.extend_from_slice(&0x00010000u32.to_be_bytes());
+        ttc.extend_from_slice(&(faces.len() as u32).to_be_bytes());
+        let header_len = ttc.len() + 4 * faces.len();
+        let mut body = vec![];
+        for face in faces {
+            let base = header_len + body.len();
+            ttc.extend_from_slice(&(base as u32).to_be_bytes());
+            let mut face = face.to_vec();
+            let table_offset = read_u32(&face, 12 + 8).unwrap() as usize + base;
+            face[12 + 8..12 + 12].copy_from_slice(&(table_offset as u32).to_be_bytes());
+            body.extend_from_slice(&face);
+        }
+        ttc.extend_from_slice(&body);
+        ttc
+    }
+
+    #[test]
+    fn extract_face_reads_the_face_at_index() {
+        let first = write_sfnt(0x00010000, &BTreeMap::from([(*b"test", vec![1])]));
+        let second = write_sfnt(
+            u32::from_be_bytes(*b"OTTO"),
+            &BTreeMap::from([(*b"test", vec![2, 3])]),
+        );
+        let ttc = build_ttc(&[&first, &second]);
+
+        let data = extract_face(&ttc, 1).unwrap();
+
+        assert_eq!(&data[..4], b"OTTO");
+        assert_eq!(read_tables(&data, 0).unwrap()[b"test"], &[2, 3]);
+        assert!(extract_face(&ttc, 2).is_err());
+    }
+
+    #[test]
+    fn face_size_counts_only_the_face_of_a_collection() {
+        let face = write_sfnt(0x00010000, &BTreeMap::from([(*b"test", vec![0; 100])]));
+        let ttc = build_ttc(&[&face, &face]);
+
+        assert_eq!(face_size(&ttc, 1), face.len());
+        assert_eq!(face_size(&face, 0), face.len());
+    }
+
+    #[test]
+    fn format_size_uses_kb_and_mb() {
+        assert_eq!(format_size(512), "0.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 + 100 * 1024), "3.1 MB");
+    }
+
+    #[test]
+    fn export_font_subsets_fails_without_font_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let preset = PresetExport {
+            name: "Preset 1".into(),
+            font_size: 24,
+            fonts: vec![],
+            custom_text: "".into(),
+        };
+
+        assert!(export_font_subsets(dir.path(), &preset, "").is_err());
+        assert!(export_font_subsets(dir.path(), &preset, "abc").is_err());
+    }
+
+    #[test]
+    fn subset_font_rejects_cff() {
+        let mut data = b"OTTO".to_vec();
+        data.extend_from_slice(&[0; 8]);
+
+        assert!(subset_font(&data, 0, "a").is_err());
+    }
+}
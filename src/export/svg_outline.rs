@@ -0,0 +1,131 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::export::outline::{PathSegment, face_name, for_each_glyph_outline};
+use crate::export::{escape_xml, number, unique_path, write_file};
+use crate::model::{FontEntry, FontFile};
+use crate::prelude::*;
+use crate::text_metrics::shape_line;
+use rustybuzz::Face;
+use rustybuzz::ttf_parser::name_id;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Fill of the glyph paths. The app has no effect settings for color or stroke yet, so the
+/// outlines are plain black shapes that are restyled in the editor.
+const FILL_COLOR: &str = "#000000";
+
+/// Writes `text` set in the font of `entry` as SVG paths to `dir` and returns the path of the
+/// written file. Each line of `text` becomes a group of glyph paths, so the result can be edited
+/// in Illustrator or Inkscape without the font installed.
+pub fn export_svg_outline(
+    dir: &Path,
+    entry: &FontEntry,
+    file: &FontFile,
+    text: &str,
+    font_size: f32,
+) -> Fallible<PathBuf> {
+    let face = Face::from_slice(&file.data, file.index)
+        .with_context(|| format!("failed to parse {}", entry.font_name))?;
+    let svg = render_svg_outline(&face, text, font_size);
+    let stem = entry.display_name.as_deref().unwrap_or(entry.font_name);
+    let path = unique_path(dir, stem, "svg");
+    write_file(&path, svg.as_bytes())?;
+    info!(path = %path.display(), "exported svg outline");
+    Ok(path)
+}
+
+fn render_svg_outline(face: &Face, text: &str, font_size: f32) -> String {
+    let scale = font_size / face.units_per_em() as f32;
+    let ascent = face.ascender() as f32 * scale;
+    let line_height = (face.ascender() - face.descender() + face.line_gap()) as f32 * scale;
+
+    let lines = text
+        .lines()
+        .map(|line| shape_line(face, line))
+        .collect::<Vec<_>>();
+    let width = lines.iter().map(|line| line.width).max().unwrap_or(0) as f32 * scale;
+    let height = line_height * lines.len() as f32;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = number(width.ceil()),
+        h = number(height.ceil()),
+    );
+    let _ = writeln!(
+        svg,
+        "  <title>{}</title>",
+        escape_xml(&face_name(face, name_id::FULL_NAME).unwrap_or_default()),
+    );
+    let _ = writeln!(svg, r#"  <g fill="{FILL_COLOR}">"#);
+
+    for (index, line) in lines.iter().enumerate() {
+        let baseline = ascent + line_height * index as f32;
+        let mut d = String::new();
+        for_each_glyph_outline(face, line, |glyph_x, glyph_y, segments| {
+            let x = glyph_x as f32 * scale;
+            let y = baseline - glyph_y as f32 * scale;
+            append_path_data(&mut d, segments, x, y, scale);
+        });
+        if !d.is_empty() {
+            let _ = writeln!(svg, r#"    <path d="{}"/>"#, d.trim_end());
+        }
+    }
+
+    svg.push_str("  </g>\n</svg>\n");
+    svg
+}
+
+/// Appends SVG path data for `segments` given in font units. The y axis is flipped since SVG
+/// coordinates point down.
+fn append_path_data(d: &mut String, segments: &[PathSegment], x: f32, y: f32, scale: f32) {
+    let p = |px: f32, py: f32| format!("{} {}", number(x + px * scale), number(y - py * scale));
+    for segment in segments {
+        let _ = match *segment {
+            PathSegment::MoveTo(px, py) => write!(d, "M{} ", p(px, py)),
+            PathSegment::LineTo(px, py) => write!(d, "L{} ", p(px, py)),
+            PathSegment::QuadTo(x1, y1, px, py) => write!(d, "Q{} {} ", p(x1, y1), p(px, py)),
+            PathSegment::CurveTo(x1, y1, x2, y2, px, py) => {
+                write!(d, "C{} {} {} ", p(x1, y1), p(x2, y2), p(px, py))
+            }
+            PathSegment::Close => write!(d, "Z "),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_path_data_flips_y_axis() {
+        let mut d = String::new();
+        append_path_data(
+            &mut d,
+            &[
+                PathSegment::MoveTo(0.0, 0.0),
+                PathSegment::LineTo(100.0, 100.0),
+                PathSegment::QuadTo(200.0, 0.0, 300.0, 100.0),
+                PathSegment::Close,
+            ],
+            10.0,
+            50.0,
+            0.1,
+        );
+        assert_eq!(d, "M10 50 L20 40 Q30 50 40 40 Z ");
+    }
+}
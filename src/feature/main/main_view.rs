@@ -13,48 +13,402 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use crate::model::{DEFAULT_SAMPLE_FONT_SIZE, FontEntry, TOOLBAR_HEIGHT, XMessage};
+use crate::data::clipboard::ClipboardRepository;
+use crate::data::font_file::FontFileRepository;
+use crate::data::font_list::FontListRepository;
+use crate::export::{export_svg_outline, render_preview_image};
+use crate::model::{
+    DEFAULT_SAMPLE_TEXT, ExportResult, ExportStatus, FontEntry, FontFile, PreviewLayout,
+    PreviewLayoutKind, SortMode, SpecimenTemplate, TOOLBAR_HEIGHT, WINDOW_BODY_MARGIN, XMessage,
+};
+use crate::prelude::*;
+use crate::text_metrics::measure_text_width;
+use crate::widget::drop_target_style;
 use iced::widget::rule::horizontal;
-use iced::widget::{column, scrollable, space, text};
-use iced::{Element, Font, Length, Subscription, Task};
+use iced::widget::{button, column, container, mouse_area, row, scrollable, sensor, space, text};
+use iced::{Alignment, Element, Font, Length, Size, Subscription, Task, mouse, padding, window};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub enum MainViewCommand {
+    CopyImageClicked(FontEntry),
+    CopyImageFinished(Result<String, String>),
+    ExportFinished(Result<ExportResult, String>),
+    ExportSvgClicked(FontEntry),
+    FontDragEnded,
+    FontDragHovered(String),
+    FontDragStarted(String),
+    FontLoaded(&'static str, Option<FontFile>),
+    HeaderResized(Size),
+    ItemMeasured(String, f32),
+    Scrolled(f32),
     SendXMessage(XMessage),
+    TextWidthsMeasured(String, Vec<(&'static str, Option<f32>)>),
+    WeightClassesMeasured(Vec<(&'static str, Option<u16>)>),
+    WindowResized(Size),
     XMessage(XMessage),
 }
 
+/// Space left at the end of a fitted headline. The width measured from the font file can differ
+/// slightly from the width laid out by iced, and the text would wrap if it were a pixel too wide.
+const FIT_TO_WIDTH_MARGIN: f32 = 8.0;
+
+const GRID_SPACING: f32 = 8.0;
+const GRID_CARD_PADDING: f32 = 8.0;
+
+/// Default line height of iced text relative to the font size. Only used to estimate the
+/// height of entries that have not been shown yet.
+const LINE_HEIGHT: f32 = 1.3;
+
+/// Height of the name, font name and buttons above the specimen rows of a list item, for the
+/// estimated height.
+const LIST_ITEM_HEADER_HEIGHT: f32 = 48.0;
+
+/// Width of the default scrollbar of iced, which is drawn over the right edge of the list.
+const SCROLLBAR_WIDTH: f32 = 10.0;
+
+/// Rows within this distance above or below the viewport are built as well, so that short
+/// scrolls don't show empty space before the next frame.
+const OVERSCAN_HEIGHT: f32 = 600.0;
+
 pub struct MainView {
+    clipboard_repo: Arc<ClipboardRepository>,
+    copy_status: Option<String>,
     custom_text: String,
-    font_size: u32,
+    export_dir: PathBuf,
+    export_status: Option<ExportStatus>,
+    font_drag: Option<FontDrag>,
+    font_file_repo: Arc<FontFileRepository>,
     font_entries: Vec<FontEntry>,
+    font_list_repo: Arc<FontListRepository>,
+    /// Position per font ID in [`SortMode::Custom`].
+    font_order: HashMap<String, usize>,
+    /// Height per font ID of the entries as laid out by iced, measured when they are shown.
+    item_heights: HashMap<String, f32>,
+    /// Incremented when [`Self::item_heights`] are cleared, so that the shown entries are
+    /// measured again even if their size did not change.
+    item_heights_generation: usize,
+    layout: PreviewLayout,
+    /// Fonts requested from [`FontListRepository::load_font`]. iced can't unload fonts, so each
+    /// font is registered once.
+    requested_fonts: HashSet<&'static str>,
+    rows: VirtualRows,
+    sort_mode: SortMode,
+    template: SpecimenTemplate,
+    /// Width in em of the sample text per font name, for the fit-to-width mode. `None` if the
+    /// font file is not available.
+    text_widths: HashMap<&'static str, Option<f32>>,
+    /// Sample text that [`Self::text_widths`] were measured for.
+    text_widths_text: String,
+    viewport: Viewport,
+    /// `usWeightClass` per font name. `None` if the font is not installed.
+    weight_classes: HashMap<&'static str, Option<u16>>,
+}
+
+/// Font being dragged to another position.
+#[derive(Debug)]
+struct FontDrag {
+    id: String,
+    /// Font under the cursor, whose position the dragged font takes when dropped.
+    target: Option<String>,
+}
+
+/// Visible area of the main view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Viewport {
+    /// Width of the content of the scrollable.
+    width: f32,
+    height: f32,
+    /// Scroll offset from the top of the content.
+    offset_y: f32,
+    /// Height of the toolbar space and the status above the list.
+    list_top: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        // Initial window size of iced until the first window event arrives.
+        Self {
+            width: 1024.0,
+            height: 768.0,
+            offset_y: 0.0,
+            list_top: TOOLBAR_HEIGHT as f32,
+        }
+    }
+}
+
+/// Rows of the list or the grid with their heights, so that only the rows near the viewport
+/// need to be built. Entries that have not been shown yet have a height estimated from the
+/// template.
+#[derive(Debug)]
+struct VirtualRows {
+    /// Indices of [`MainView::font_entries`] per row in the sort order. A row of the list
+    /// contains one entry.
+    rows: Vec<Vec<usize>>,
+    /// Top of each row followed by the total height, so `tops.len() == rows.len() + 1`.
+    tops: Vec<f32>,
+}
+
+impl Default for VirtualRows {
+    fn default() -> Self {
+        Self {
+            rows: vec![],
+            tops: vec![0.0],
+        }
+    }
+}
+
+impl VirtualRows {
+    fn total_height(&self) -> f32 {
+        self.tops.last().copied().unwrap_or_default()
+    }
 }
 
 impl MainView {
-    pub fn new() -> Self {
+    pub fn new(
+        font_list_repo: Arc<FontListRepository>,
+        font_file_repo: Arc<FontFileRepository>,
+        clipboard_repo: Arc<ClipboardRepository>,
+        export_dir: PathBuf,
+    ) -> Self {
         Self {
+            clipboard_repo,
+            copy_status: None,
             custom_text: "".to_owned(),
-            font_size: DEFAULT_SAMPLE_FONT_SIZE,
+            export_dir,
+            export_status: None,
+            font_drag: None,
+            font_file_repo,
             font_entries: vec![],
+            font_list_repo,
+            font_order: HashMap::new(),
+            item_heights: HashMap::new(),
+            item_heights_generation: 0,
+            layout: PreviewLayout::default(),
+            requested_fonts: HashSet::new(),
+            rows: VirtualRows::default(),
+            sort_mode: SortMode::default(),
+            template: SpecimenTemplate::default(),
+            text_widths: HashMap::new(),
+            text_widths_text: String::new(),
+            viewport: Viewport::default(),
+            weight_classes: HashMap::new(),
         }
     }
 
     pub fn update(&mut self, command: MainViewCommand) -> Task<MainViewCommand> {
         match command {
+            MainViewCommand::CopyImageClicked(font_entry) => {
+                let sample_text = self.sample_text().to_string();
+                let font_size = self.template.headline_size() as f32;
+                let font_list_repo = self.font_list_repo.clone();
+                let font_file_repo = self.font_file_repo.clone();
+                let clipboard_repo = self.clipboard_repo.clone();
+                Task::perform(
+                    async move {
+                        let file = font_list_repo
+                            .load_font(&font_entry)
+                            .or_else(|| font_file_repo.find_by_name(font_entry.font_name))
+                            .with_context(|| {
+                                format!("font file of {} is not found", font_entry.font_name)
+                            })?;
+                        let image = render_preview_image(&file, &sample_text, font_size)?;
+                        clipboard_repo.write_image(&image)?;
+                        Ok(font_entry
+                            .display_name
+                            .clone()
+                            .unwrap_or_else(|| font_entry.font_name.to_string()))
+                    },
+                    |result: Fallible<String>| {
+                        if let Err(e) = &result {
+                            warn!(?e, "failed to copy image");
+                        }
+                        MainViewCommand::CopyImageFinished(result.map_err(|e| format!("{e:#}")))
+                    },
+                )
+            }
+            MainViewCommand::CopyImageFinished(result) => {
+                self.copy_status = Some(match result {
+                    Ok(name) => format!("Copied {name} to the clipboard"),
+                    Err(e) => format!("Copy failed: {e}"),
+                });
+                Task::none()
+            }
+            MainViewCommand::ExportFinished(result) => {
+                self.copy_status = None;
+                self.export_status = Some(result.into());
+                Task::none()
+            }
+            MainViewCommand::ExportSvgClicked(font_entry) => {
+                if matches!(self.export_status, Some(ExportStatus::Running)) {
+                    return Task::none();
+                }
+                self.copy_status = None;
+                self.export_status = Some(ExportStatus::Running);
+
+                let sample_text = self.sample_text().to_string();
+                let font_size = self.template.headline_size() as f32;
+                let font_list_repo = self.font_list_repo.clone();
+                let font_file_repo = self.font_file_repo.clone();
+                let export_dir = self.export_dir.clone();
+                Task::perform(
+                    async move {
+                        let file = font_list_repo
+                            .load_font(&font_entry)
+                            .or_else(|| font_file_repo.find_by_name(font_entry.font_name))
+                            .with_context(|| {
+                                format!("font file of {} is not found", font_entry.font_name)
+                            })?;
+                        export_svg_outline(&export_dir, &font_entry, &file, &sample_text, font_size)
+                    },
+                    |result| {
+                        if let Err(e) = &result {
+                            warn!(?e, "failed to export svg");
+                        }
+                        MainViewCommand::ExportFinished(
+                            result.map(ExportResult::from).map_err(|e| format!("{e:#}")),
+                        )
+                    },
+                )
+            }
+            MainViewCommand::FontDragEnded => {
+                let Some(FontDrag {
+                    id,
+                    target: Some(target),
+                }) = self.font_drag.take()
+                else {
+                    return Task::none();
+                };
+                let Some(ids) = self.arranged_ids(&id, &target) else {
+                    return Task::none();
+                };
+                // SettingsView stores the order in the preset and sends it back as FontOrder.
+                Task::done(MainViewCommand::SendXMessage(XMessage::FontsArranged(ids)))
+            }
+            MainViewCommand::FontDragHovered(id) => {
+                if let Some(drag) = self.font_drag.as_mut() {
+                    drag.target = Some(id);
+                }
+                Task::none()
+            }
+            MainViewCommand::FontDragStarted(id) => {
+                self.font_drag = Some(FontDrag { id, target: None });
+                Task::none()
+            }
+            MainViewCommand::FontLoaded(font_name, file) => match file {
+                Some(file) => {
+                    let font_list_repo = self.font_list_repo.clone();
+                    let bytes = file.data.len();
+                    iced::font::load(file.data.to_vec()).then(move |result| {
+                        match result {
+                            Ok(_) => font_list_repo.record_registered_font(font_name, bytes),
+                            Err(e) => warn!(?e, font_name, "failed to register font"),
+                        }
+                        Task::none()
+                    })
+                }
+                None => Task::none(),
+            },
+            MainViewCommand::HeaderResized(size) => {
+                if self.viewport.width != size.width {
+                    self.viewport.width = size.width;
+                    self.invalidate_item_heights();
+                }
+                self.viewport.list_top = size.height;
+                self.relayout();
+                self.visible_rows_changed()
+            }
+            MainViewCommand::ItemMeasured(id, height) => {
+                if let Some(data) = self.item_heights.get(&id)
+                    && (data - height).abs() < 0.5
+                {
+                    return Task::none();
+                }
+                self.item_heights.insert(id, height);
+                self.relayout();
+                self.visible_rows_changed()
+            }
+            MainViewCommand::Scrolled(offset_y) => {
+                self.viewport.offset_y = offset_y;
+                self.visible_rows_changed()
+            }
             // Propagate to App layer via Task so it can be converted to AppCommand::XMessage.
             MainViewCommand::SendXMessage(data) => Task::done(MainViewCommand::SendXMessage(data)),
+            MainViewCommand::TextWidthsMeasured(sample_text, widths) => {
+                // Drop the results for a text that has been edited in the meantime.
+                if sample_text != self.text_widths_text {
+                    return Task::none();
+                }
+                self.text_widths.extend(widths);
+                if self.template.fit_to_width {
+                    self.invalidate_item_heights();
+                }
+                self.relayout();
+                // Fitted sizes change the row heights and may bring other rows into view.
+                self.visible_rows_changed()
+            }
+            MainViewCommand::WeightClassesMeasured(weight_classes) => {
+                self.weight_classes.extend(weight_classes);
+                self.relayout();
+                Task::none()
+            }
+            // The width of the list is measured by HeaderResized.
+            MainViewCommand::WindowResized(size) => {
+                self.viewport.height = size.height;
+                self.relayout();
+                self.visible_rows_changed()
+            }
             MainViewCommand::XMessage(message) => match message {
                 XMessage::CustomText(value) => {
                     self.custom_text = value;
+                    self.invalidate_item_heights();
+                    self.relayout();
+                    self.visible_rows_changed()
+                }
+                // Fonts whose file has been moved or replaced are registered again. FontEntries
+                // follows with the entries to show.
+                XMessage::FontCatalog(catalog) => {
+                    self.requested_fonts
+                        .retain(|font_name| !catalog.changed_font_names.contains(font_name));
                     Task::none()
                 }
                 XMessage::FontEntries(entries) => {
                     self.font_entries = entries;
-                    Task::none()
+                    self.relayout();
+                    Task::batch([self.visible_rows_changed(), self.measure_weight_classes()])
                 }
-                XMessage::FontSize(size) => {
-                    self.font_size = size;
-                    Task::none()
+                XMessage::FontOrder(ids) => {
+                    self.font_order = ids
+                        .into_iter()
+                        .enumerate()
+                        .map(|(position, id)| (id, position))
+                        .collect();
+                    if self.sort_mode != SortMode::Custom {
+                        return Task::none();
+                    }
+                    self.relayout();
+                    self.visible_rows_changed()
+                }
+                XMessage::PreviewLayout(layout) => {
+                    self.layout = layout;
+                    self.invalidate_item_heights();
+                    self.relayout();
+                    self.visible_rows_changed()
+                }
+                XMessage::SortMode(sort_mode) => {
+                    self.sort_mode = sort_mode;
+                    self.relayout();
+                    Task::batch([self.visible_rows_changed(), self.measure_weight_classes()])
+                }
+                XMessage::SpecimenTemplate(template) => {
+                    self.template = template;
+                    self.invalidate_item_heights();
+                    self.relayout();
+                    self.visible_rows_changed()
                 }
                 _ => Task::none(),
             },
@@ -62,74 +416,822 @@ impl MainView {
     }
 
     pub fn subscription(&self) -> Subscription<MainViewCommand> {
-        Subscription::none()
+        let window = iced::event::listen_with(|event, _status, _id| match event {
+            iced::Event::Window(window::Event::Opened { size, .. })
+            | iced::Event::Window(window::Event::Resized(size)) => {
+                Some(MainViewCommand::WindowResized(size))
+            }
+            _ => None,
+        });
+        // The button may be released outside of the fonts, which cancels the drag.
+        let drag = if self.font_drag.is_some() {
+            iced::event::listen_with(|event, _status, _id| match event {
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(MainViewCommand::FontDragEnded)
+                }
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+        Subscription::batch([window, drag])
     }
 
     pub fn view(&self) -> Element<'_, MainViewCommand> {
-        let mut content = column![space().height(TOOLBAR_HEIGHT)];
+        let mut header = column![space().height(TOOLBAR_HEIGHT)].width(Length::Fill);
 
-        let mut first = true;
-        for entry in &self.font_entries {
-            if !first {
-                content = content.push(horizontal(1));
-            } else {
-                first = false;
-            }
+        let status = match (&self.copy_status, &self.export_status) {
+            (Some(data), _) => Some(data.clone()),
+            (None, Some(data)) => Some(data.to_string()),
+            (None, None) => None,
+        };
+        if let Some(status) = status {
+            header = header.push(
+                container(text(status).size(12)).padding(padding::horizontal(WINDOW_BODY_MARGIN)),
+            );
+        }
+
+        let range = self.visible_rows();
+        let mut entries = column![space().height(self.rows.tops[range.start])];
+        for index in range.clone() {
+            entries = entries.push(match self.layout.kind {
+                PreviewLayoutKind::List => self.view_list_row(&self.rows.rows[index]),
+                PreviewLayoutKind::Grid => self.view_grid_row(&self.rows.rows[index]),
+            });
+        }
+        entries =
+            entries.push(space().height(self.rows.total_height() - self.rows.tops[range.end]));
+
+        // The header tells the width of the list and where the list starts.
+        let content = column![
+            sensor(header).on_resize(MainViewCommand::HeaderResized),
+            entries
+        ];
+        scrollable(content.width(Length::Fill))
+            .on_scroll(|viewport| MainViewCommand::Scrolled(viewport.absolute_offset().y))
+            .into()
+    }
 
-            content = content.push(list_item(
+    fn view_list_row(&self, indices: &[usize]) -> Element<'_, MainViewCommand> {
+        let export_enabled = !matches!(self.export_status, Some(ExportStatus::Running));
+        let mut content = column![];
+        for &index in indices {
+            let entry = &self.font_entries[index];
+            let fit_size = self
+                .template
+                .fit_to_width
+                .then(|| self.fit_size(entry, self.fit_width()));
+            content = content.push(self.draggable(
                 entry,
-                self.font_size,
-                if self.custom_text.is_empty() {
-                    "あのイーハトーヴォのすきとおった風、夏でも底に冷たさをもつ青いそら、うつくしい森で飾られたモリーオ市、郊外のぎらぎらひかる草の波。"
-                } else {
-                    &self.custom_text
+                self.measured(
+                    entry,
+                    list_item(
+                        entry,
+                        &self.template,
+                        fit_size,
+                        self.sample_text(),
+                        export_enabled,
+                    ),
+                ),
+            ));
+        }
+
+        column![content, horizontal(1)].into()
+    }
+
+    fn view_grid_row(&self, indices: &[usize]) -> Element<'_, MainViewCommand> {
+        let columns = self.grid_columns();
+        let mut cards = row![].spacing(GRID_SPACING);
+        for &index in indices {
+            let entry = &self.font_entries[index];
+            cards = cards.push(self.draggable(
+                entry,
+                self.measured(
+                    entry,
+                    grid_card(entry, self.headline_size(entry), self.sample_text()),
+                ),
+            ));
+        }
+        // Keep the cards of the last row as wide as the others.
+        for _ in indices.len()..columns {
+            cards = cards.push(space().width(Length::Fill));
+        }
+
+        container(cards)
+            .padding(padding::horizontal(WINDOW_BODY_MARGIN).bottom(GRID_SPACING))
+            .into()
+    }
+
+    /// Reports the height of the entry as [`MainViewCommand::ItemMeasured`] when it is shown and
+    /// whenever it changes, e.g. once the font is loaded.
+    fn measured<'a>(
+        &self,
+        font_entry: &FontEntry,
+        content: Element<'a, MainViewCommand>,
+    ) -> Element<'a, MainViewCommand> {
+        let id = font_entry.id.clone();
+        let measured = move |size: Size| MainViewCommand::ItemMeasured(id.clone(), size.height);
+        sensor(content)
+            .key((font_entry.id.clone(), self.item_heights_generation))
+            .anticipate(OVERSCAN_HEIGHT)
+            .on_show(measured.clone())
+            .on_resize(measured)
+            .into()
+    }
+
+    /// Lets the entry be dragged to the position of another entry.
+    fn draggable<'a>(
+        &self,
+        font_entry: &FontEntry,
+        content: Element<'a, MainViewCommand>,
+    ) -> Element<'a, MainViewCommand> {
+        let id = &font_entry.id;
+        let area = mouse_area(content).on_press(MainViewCommand::FontDragStarted(id.clone()));
+        let Some(drag) = &self.font_drag else {
+            return area.interaction(mouse::Interaction::Grab).into();
+        };
+
+        let area = area
+            .on_enter(MainViewCommand::FontDragHovered(id.clone()))
+            .interaction(mouse::Interaction::Grabbing);
+        if drag.target.as_ref() == Some(id) && &drag.id != id {
+            container(area).style(drop_target_style).into()
+        } else {
+            area.into()
+        }
+    }
+
+    fn grid_columns(&self) -> usize {
+        match self.layout.kind {
+            PreviewLayoutKind::List => 1,
+            PreviewLayoutKind::Grid => self.layout.grid_columns.max(1) as usize,
+        }
+    }
+
+    /// Width available for the headline of an entry.
+    fn fit_width(&self) -> f32 {
+        match self.layout.kind {
+            PreviewLayoutKind::List => self.viewport.width - SCROLLBAR_WIDTH,
+            PreviewLayoutKind::Grid => {
+                let columns = self.grid_columns() as f32;
+                let width = self.viewport.width
+                    - 2.0 * WINDOW_BODY_MARGIN as f32
+                    - GRID_SPACING * (columns - 1.0);
+                width / columns - 2.0 * GRID_CARD_PADDING
+            }
+        }
+    }
+
+    fn headline_size(&self, font_entry: &FontEntry) -> u32 {
+        match self.template.fit_to_width {
+            true => match self.fit_size(font_entry, self.fit_width()) {
+                FitSize::Fitted(size) => size,
+                _ => self.template.headline_size(),
+            },
+            false => self.template.headline_size(),
+        }
+    }
+
+    /// Height of an entry as laid out by iced, or an estimate from the font sizes and the
+    /// number of lines if it has not been shown yet.
+    fn item_height(&self, font_entry: &FontEntry) -> f32 {
+        if let Some(height) = self.item_heights.get(&font_entry.id) {
+            return *height;
+        }
+
+        let headline_lines = line_count(self.sample_text());
+        match self.layout.kind {
+            PreviewLayoutKind::List => {
+                let rows = self.template.rows.iter().filter(|row| row.enabled);
+                LIST_ITEM_HEADER_HEIGHT
+                    + rows
+                        .map(|row| match row.kind.sample_text() {
+                            Some(sample_text) => {
+                                text_height(row.font_size, line_count(sample_text))
+                            }
+                            None => text_height(self.headline_size(font_entry), headline_lines),
+                        })
+                        .sum::<f32>()
+            }
+            PreviewLayoutKind::Grid => {
+                2.0 * GRID_CARD_PADDING
+                    + text_height(12, 1)
+                    + text_height(self.headline_size(font_entry), headline_lines)
+            }
+        }
+    }
+
+    /// Forgets the measured heights after a change that affects the layout of every entry.
+    fn invalidate_item_heights(&mut self) {
+        self.item_heights.clear();
+        self.item_heights_generation += 1;
+    }
+
+    /// Rebuilds [`Self::rows`] after a change of the entries, their order or their heights.
+    fn relayout(&mut self) {
+        let separator = match self.layout.kind {
+            PreviewLayoutKind::List => 1.0,
+            PreviewLayoutKind::Grid => GRID_SPACING,
+        };
+        let indices = self.sorted_indices();
+        let mut rows = VirtualRows {
+            rows: vec![],
+            tops: vec![0.0],
+        };
+        for chunk in indices.chunks(self.grid_columns()) {
+            let height = chunk
+                .iter()
+                .map(|&index| self.item_height(&self.font_entries[index]))
+                .fold(0.0, f32::max);
+            rows.tops.push(rows.total_height() + height + separator);
+            rows.rows.push(chunk.to_vec());
+        }
+        self.rows = rows;
+    }
+
+    /// Rows inside or near the viewport.
+    fn visible_rows(&self) -> Range<usize> {
+        let offset_y = self.viewport.offset_y - self.viewport.list_top;
+        let top = offset_y - OVERSCAN_HEIGHT;
+        let bottom = offset_y + self.viewport.height + OVERSCAN_HEIGHT;
+        let len = self.rows.rows.len();
+        let start = self.rows.tops[1..].partition_point(|&y| y <= top);
+        let end = self.rows.tops[..len].partition_point(|&y| y < bottom);
+        start..end.max(start)
+    }
+
+    fn fit_size(&self, font_entry: &FontEntry, available_width: f32) -> FitSize {
+        match self.text_widths.get(font_entry.font_name) {
+            None => FitSize::Measuring,
+            Some(None) => FitSize::Unavailable,
+            Some(Some(em)) if *em <= 0.0 => FitSize::Unavailable,
+            Some(Some(em)) => {
+                let size = ((available_width - FIT_TO_WIDTH_MARGIN) / em).floor();
+                FitSize::Fitted(size.clamp(1.0, 1000.0) as u32)
+            }
+        }
+    }
+
+    /// Indices of the entries in the order of [`Self::sort_mode`]. Sorting is stable, so entries
+    /// with the same key, or without a measured metric, keep the catalog order after the others.
+    fn sorted_indices(&self) -> Vec<usize> {
+        let entries = &self.font_entries;
+        let mut indices = (0..entries.len()).collect::<Vec<_>>();
+        match self.sort_mode {
+            SortMode::Catalog => {}
+            SortMode::Name => {
+                indices.sort_by_cached_key(|&index| entry_name(&entries[index]).to_lowercase())
+            }
+            SortMode::Collection => indices
+                .sort_by(|&a, &b| entry_collection(&entries[a]).cmp(entry_collection(&entries[b]))),
+            SortMode::WeightClass => indices.sort_by_key(|&index| {
+                let weight_class = self
+                    .weight_classes
+                    .get(entries[index].font_name)
+                    .copied()
+                    .flatten();
+                (weight_class.is_none(), weight_class)
+            }),
+            SortMode::RenderedWidth => indices.sort_by(|&a, &b| {
+                let width = |index: usize| {
+                    self.text_widths
+                        .get(entries[index].font_name)
+                        .copied()
+                        .flatten()
+                };
+                match (width(a), width(b)) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            }),
+            SortMode::Custom => indices.sort_by_key(|&index| {
+                self.font_order
+                    .get(&entries[index].id)
+                    .copied()
+                    .unwrap_or(usize::MAX)
+            }),
+        }
+        indices
+    }
+
+    /// IDs of the shown fonts in the sort order after moving `id` to the position of `target`.
+    /// `None` if nothing moves.
+    fn arranged_ids(&self, id: &str, target: &str) -> Option<Vec<String>> {
+        let mut ids = self
+            .sorted_indices()
+            .into_iter()
+            .map(|index| self.font_entries[index].id.clone())
+            .collect::<Vec<_>>();
+        let from = ids.iter().position(|data| data == id)?;
+        let to = ids.iter().position(|data| data == target)?;
+        if from == to {
+            return None;
+        }
+        let id = ids.remove(from);
+        ids.insert(to, id);
+        Some(ids)
+    }
+
+    fn visible_rows_changed(&mut self) -> Task<MainViewCommand> {
+        Task::batch([self.measure_text_widths(), self.load_visible_fonts()])
+    }
+
+    /// Loads the fonts of the rows near the viewport that have not been requested yet. Fonts
+    /// without a file in the pack fall back to the installed fonts of the same name.
+    fn load_visible_fonts(&mut self) -> Task<MainViewCommand> {
+        let font_entries = self.rows.rows[self.visible_rows()]
+            .iter()
+            .flatten()
+            .map(|&index| &self.font_entries[index])
+            .filter(|entry| self.requested_fonts.insert(entry.font_name))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Task::batch(font_entries.into_iter().map(|entry| {
+            let font_list_repo = self.font_list_repo.clone();
+            Task::perform(
+                async move {
+                    let file = font_list_repo.load_font(&entry);
+                    (entry.font_name, file)
                 },
-             ));
+                |(font_name, file)| MainViewCommand::FontLoaded(font_name, file),
+            )
+        }))
+    }
+
+    fn measure_weight_classes(&mut self) -> Task<MainViewCommand> {
+        if self.sort_mode != SortMode::WeightClass {
+            return Task::none();
+        }
+
+        let font_entries = self
+            .font_entries
+            .iter()
+            .filter(|entry| !self.weight_classes.contains_key(entry.font_name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if font_entries.is_empty() {
+            return Task::none();
         }
 
-        scrollable(content.width(Length::Fill)).into()
+        // Read without caching so that the scan doesn't evict the fonts on screen.
+        let font_list_repo = self.font_list_repo.clone();
+        let font_file_repo = self.font_file_repo.clone();
+        Task::perform(
+            async move {
+                font_entries
+                    .into_iter()
+                    .map(|entry| {
+                        let weight_class = match font_list_repo.read_font_file(&entry) {
+                            Some(file) => rustybuzz::Face::from_slice(&file.data, file.index)
+                                .map(|face| face.weight().to_number()),
+                            None => font_file_repo.find_weight_class(entry.font_name),
+                        };
+                        (entry.font_name, weight_class)
+                    })
+                    .collect::<Vec<_>>()
+            },
+            MainViewCommand::WeightClassesMeasured,
+        )
     }
-}
 
-impl Default for MainView {
-    fn default() -> Self {
-        Self::new()
+    /// Measures the sample text in the fonts that have not been measured yet. Only runs in the
+    /// fit-to-width mode or when sorting by width since reading font files is expensive, and
+    /// only for the rows near the viewport unless the order depends on the widths.
+    fn measure_text_widths(&mut self) -> Task<MainViewCommand> {
+        if !self.template.fit_to_width && self.sort_mode != SortMode::RenderedWidth {
+            return Task::none();
+        }
+
+        let sample_text = self.sample_text().to_string();
+        if sample_text != self.text_widths_text {
+            self.text_widths.clear();
+            self.text_widths_text = sample_text.clone();
+        }
+
+        let indices = match self.sort_mode {
+            SortMode::RenderedWidth => (0..self.font_entries.len()).collect(),
+            _ => self.rows.rows[self.visible_rows()].concat(),
+        };
+        let font_entries = indices
+            .into_iter()
+            .map(|index| &self.font_entries[index])
+            .filter(|entry| !self.text_widths.contains_key(entry.font_name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if font_entries.is_empty() {
+            return Task::none();
+        }
+
+        let font_list_repo = self.font_list_repo.clone();
+        let font_file_repo = self.font_file_repo.clone();
+        Task::perform(
+            async move {
+                let widths = font_entries
+                    .into_iter()
+                    .map(|entry| {
+                        let width = font_list_repo
+                            .read_font_file(&entry)
+                            .or_else(|| font_file_repo.find_by_name(entry.font_name))
+                            .and_then(|file| measure_text_width(&file, &sample_text).ok());
+                        (entry.font_name, width)
+                    })
+                    .collect::<Vec<_>>();
+                (sample_text, widths)
+            },
+            |(sample_text, widths)| MainViewCommand::TextWidthsMeasured(sample_text, widths),
+        )
+    }
+
+    fn sample_text(&self) -> &str {
+        if self.custom_text.is_empty() {
+            DEFAULT_SAMPLE_TEXT
+        } else {
+            &self.custom_text
+        }
     }
 }
 
+fn line_count(text: &str) -> usize {
+    text.lines().count().max(1)
+}
+
+fn text_height(font_size: u32, lines: usize) -> f32 {
+    font_size as f32 * LINE_HEIGHT * lines as f32
+}
+
+fn entry_name(font_entry: &FontEntry) -> &str {
+    font_entry
+        .display_name
+        .as_deref()
+        .unwrap_or(font_entry.font_name)
+}
+
+/// Folder of the font file in the pack, e.g. `./桜/02_uヴュー(12)`.
+fn entry_collection(font_entry: &FontEntry) -> &str {
+    Path::new(&font_entry.filepath)
+        .parent()
+        .and_then(Path::to_str)
+        .unwrap_or_default()
+}
+
+/// Headline size of an entry in the fit-to-width mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FitSize {
+    Measuring,
+    Unavailable,
+    Fitted(u32),
+}
+
 fn list_item<'a>(
     font_entry: &'a FontEntry,
-    font_size: u32,
+    template: &SpecimenTemplate,
+    fit_size: Option<FitSize>,
     message: &'a str,
+    export_enabled: bool,
 ) -> Element<'a, MainViewCommand> {
-    let content = column![];
-
-    let content = match font_entry.display_name {
-        Some(ref data) => content.push(text(data)),
-        None => content.push(font_entry.font_name),
+    let title = match font_entry.display_name {
+        Some(ref data) => text(data),
+        None => text(font_entry.font_name),
     };
 
-    content
-        .push(text(font_entry.font_name))
-        .push(
-            text(message)
+    let copy_image_btn = button(text("Copy image").size(12))
+        .style(button::text)
+        .on_press(MainViewCommand::CopyImageClicked(font_entry.clone()));
+
+    let mut export_svg_btn = button(text("SVG").size(12)).style(button::text);
+    if export_enabled {
+        export_svg_btn =
+            export_svg_btn.on_press(MainViewCommand::ExportSvgClicked(font_entry.clone()));
+    }
+
+    let fit_label = fit_size.map(|data| {
+        let label = match data {
+            FitSize::Measuring => "Fit: ...".to_string(),
+            FitSize::Unavailable => "Fit: n/a".to_string(),
+            FitSize::Fitted(size) => format!("Fit: {size}px"),
+        };
+        text(label).size(12)
+    });
+
+    let mut content = column![
+        row![title, space().width(Length::Fill)]
+            .push(fit_label)
+            .push(copy_image_btn)
+            .push(export_svg_btn)
+            .align_y(Alignment::Center)
+    ]
+    .push(text(font_entry.font_name));
+
+    for row in template.rows.iter().filter(|row| row.enabled) {
+        let font_size = match (row.kind.sample_text(), fit_size) {
+            (None, Some(FitSize::Fitted(size))) => size,
+            _ => row.font_size,
+        };
+        content = content.push(
+            text(row.kind.sample_text().unwrap_or(message))
                 .size(font_size)
                 .font(Font::with_name(font_entry.font_name)),
-        )
-        .into()
+        );
+    }
+
+    content.into()
+}
+
+fn grid_card<'a>(
+    font_entry: &'a FontEntry,
+    font_size: u32,
+    message: &'a str,
+) -> Element<'a, MainViewCommand> {
+    container(column![
+        text(entry_name(font_entry)).size(12),
+        text(message)
+            .size(font_size)
+            .font(Font::with_name(font_entry.font_name)),
+    ])
+    .style(container::bordered_box)
+    .padding(GRID_CARD_PADDING)
+    .width(Length::Fill)
+    .into()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::font_list::FontListRepository;
     use iced::Theme;
 
+    fn create_main_view() -> MainView {
+        MainView::new(
+            Arc::new(FontListRepository::default()),
+            Arc::new(FontFileRepository::default()),
+            Arc::new(ClipboardRepository::default()),
+            std::env::temp_dir(),
+        )
+    }
+
+    #[test]
+    fn export_svg_clicked_sets_running() {
+        let font_entries = FontListRepository::default().find_all();
+        let mut main_view = create_main_view();
+
+        let _ = main_view.update(MainViewCommand::ExportSvgClicked(font_entries[0].clone()));
+
+        assert!(matches!(
+            main_view.export_status,
+            Some(ExportStatus::Running)
+        ));
+    }
+
+    #[test]
+    fn export_finished_updates_status() {
+        let mut main_view = create_main_view();
+
+        let _ = main_view.update(MainViewCommand::ExportFinished(Err("error".into())));
+
+        assert!(matches!(
+            main_view.export_status,
+            Some(ExportStatus::Failed(_))
+        ));
+    }
+
+    #[test]
+    fn copy_image_finished_updates_status() {
+        let mut main_view = create_main_view();
+
+        let _ = main_view.update(MainViewCommand::CopyImageFinished(Ok("UビューE".into())));
+        assert_eq!(
+            main_view.copy_status.as_deref(),
+            Some("Copied UビューE to the clipboard"),
+        );
+
+        let _ = main_view.update(MainViewCommand::ExportFinished(Err("error".into())));
+        assert_eq!(main_view.copy_status, None);
+    }
+
+    #[test]
+    fn text_widths_measured_drops_stale_text() {
+        let font_entries = FontListRepository::default().find_all();
+        let mut main_view = create_main_view();
+        let template = SpecimenTemplate {
+            fit_to_width: true,
+            ..Default::default()
+        };
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::SpecimenTemplate(
+            template,
+        )));
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::CustomText(
+            "Title".into(),
+        )));
+
+        let _ = main_view.update(MainViewCommand::TextWidthsMeasured(
+            "Tit".into(),
+            vec![(font_entries[0].font_name, Some(2.0))],
+        ));
+        assert!(main_view.text_widths.is_empty());
+
+        let _ = main_view.update(MainViewCommand::TextWidthsMeasured(
+            "Title".into(),
+            vec![(font_entries[0].font_name, Some(2.0))],
+        ));
+        assert_eq!(
+            main_view.fit_size(&font_entries[0], 208.0),
+            FitSize::Fitted(100)
+        );
+        assert_eq!(
+            main_view.fit_size(&font_entries[1], 208.0),
+            FitSize::Measuring
+        );
+    }
+
+    fn create_sorted_main_view(sort_mode: SortMode) -> MainView {
+        let mut main_view = create_main_view();
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::FontEntries(vec![
+            FontEntry::new("Zeta".into(), "./b/zeta.ttf".into(), None, "Zeta"),
+            FontEntry::new(
+                "Beta".into(),
+                "./a/beta.ttf".into(),
+                Some("beta".into()),
+                "Beta",
+            ),
+            FontEntry::new("Alpha".into(), "./b/alpha.ttf".into(), None, "Alpha"),
+        ])));
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::SortMode(sort_mode)));
+        main_view
+    }
+
+    fn sorted_font_names(main_view: &MainView) -> Vec<&'static str> {
+        main_view
+            .sorted_indices()
+            .into_iter()
+            .map(|index| main_view.font_entries[index].font_name)
+            .collect()
+    }
+
+    #[test]
+    fn sort_mode_orders_entries() {
+        let main_view = create_sorted_main_view(SortMode::Catalog);
+        assert_eq!(sorted_font_names(&main_view), vec!["Zeta", "Beta", "Alpha"]);
+
+        let main_view = create_sorted_main_view(SortMode::Name);
+        assert_eq!(sorted_font_names(&main_view), vec!["Alpha", "Beta", "Zeta"]);
+
+        let main_view = create_sorted_main_view(SortMode::Collection);
+        assert_eq!(sorted_font_names(&main_view), vec!["Beta", "Zeta", "Alpha"]);
+    }
+
+    #[test]
+    fn sort_mode_puts_unmeasured_entries_last() {
+        let mut main_view = create_sorted_main_view(SortMode::WeightClass);
+        let _ = main_view.update(MainViewCommand::WeightClassesMeasured(vec![
+            ("Zeta", Some(700)),
+            ("Beta", None),
+            ("Alpha", Some(400)),
+        ]));
+        assert_eq!(sorted_font_names(&main_view), vec!["Alpha", "Zeta", "Beta"]);
+
+        let mut main_view = create_sorted_main_view(SortMode::RenderedWidth);
+        let _ = main_view.update(MainViewCommand::TextWidthsMeasured(
+            DEFAULT_SAMPLE_TEXT.into(),
+            vec![("Zeta", Some(30.0)), ("Beta", Some(20.0))],
+        ));
+        assert_eq!(sorted_font_names(&main_view), vec!["Beta", "Zeta", "Alpha"]);
+    }
+
+    #[test]
+    fn custom_sort_mode_follows_font_order() {
+        let mut main_view = create_sorted_main_view(SortMode::Custom);
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::FontOrder(vec![
+            "Alpha".into(),
+            "Gone".into(),
+            "Zeta".into(),
+        ])));
+        assert_eq!(sorted_font_names(&main_view), vec!["Alpha", "Zeta", "Beta"]);
+
+        assert_eq!(
+            main_view.arranged_ids("Beta", "Alpha"),
+            Some(vec!["Beta".into(), "Alpha".into(), "Zeta".into()])
+        );
+        assert_eq!(
+            main_view.arranged_ids("Alpha", "Zeta"),
+            Some(vec!["Zeta".into(), "Alpha".into(), "Beta".into()])
+        );
+        assert_eq!(main_view.arranged_ids("Alpha", "Alpha"), None);
+
+        let _ = main_view.update(MainViewCommand::FontDragStarted("Alpha".into()));
+        let _ = main_view.update(MainViewCommand::FontDragHovered("Zeta".into()));
+        let _ = main_view.view();
+        let task = main_view.update(MainViewCommand::FontDragEnded);
+        assert_eq!(task.units(), 1);
+        assert!(main_view.font_drag.is_none());
+    }
+
+    #[test]
+    fn preview_layout_switches_view() {
+        let mut main_view = create_sorted_main_view(SortMode::Catalog);
+        let layout = PreviewLayout {
+            kind: PreviewLayoutKind::Grid,
+            grid_columns: 2,
+        };
+
+        let task = main_view.update(MainViewCommand::XMessage(XMessage::PreviewLayout(layout)));
+
+        assert_eq!(task.units(), 0);
+        assert_eq!(main_view.layout, layout);
+        assert_eq!(main_view.rows.rows, vec![vec![0, 1], vec![2]]);
+        let _ = main_view.view();
+    }
+
+    #[test]
+    fn visible_rows_follow_scroll_offset() {
+        let mut main_view = create_main_view();
+        let font_entries = (0..200)
+            .map(|index| FontEntry::new(index.to_string(), format!("./{index}.ttf"), None, "Arial"))
+            .collect();
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::FontEntries(
+            font_entries,
+        )));
+
+        let range = main_view.visible_rows();
+        assert_eq!(range.start, 0);
+        assert!(range.end < 200);
+
+        let offset_y = main_view.rows.total_height() - main_view.viewport.height;
+        let _ = main_view.update(MainViewCommand::Scrolled(offset_y));
+
+        let range = main_view.visible_rows();
+        assert!(range.start > 0);
+        assert_eq!(range.end, 200);
+        let _ = main_view.view();
+    }
+
+    #[test]
+    fn visible_rows_start_below_header() {
+        let mut main_view = create_sorted_main_view(SortMode::Catalog);
+        assert_eq!(main_view.visible_rows(), 0..3);
+
+        let _ = main_view.update(MainViewCommand::HeaderResized(Size::new(800.0, 2000.0)));
+
+        assert!(main_view.visible_rows().is_empty());
+        assert_eq!(main_view.fit_width(), 800.0 - SCROLLBAR_WIDTH);
+    }
+
+    #[test]
+    fn item_measured_replaces_estimated_height() {
+        let mut main_view = create_sorted_main_view(SortMode::Catalog);
+        let estimated = main_view.rows.tops[1];
+
+        let _ = main_view.update(MainViewCommand::ItemMeasured("Zeta".into(), 500.0));
+        assert_eq!(main_view.rows.tops[1], 501.0);
+
+        let task = main_view.update(MainViewCommand::ItemMeasured("Zeta".into(), 500.2));
+        assert_eq!(task.units(), 0);
+
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::CustomText(
+            "Title\nSubtitle".into(),
+        )));
+        assert!(main_view.item_heights.is_empty());
+        assert!(main_view.rows.tops[1] > estimated);
+        let _ = main_view.view();
+    }
+
+    #[test]
+    fn measure_text_widths_noop_when_fit_to_width_disabled() {
+        let font_entries = FontListRepository::default().find_all();
+        let mut main_view = create_main_view();
+
+        let _ = main_view.update(MainViewCommand::XMessage(XMessage::FontEntries(
+            font_entries,
+        )));
+
+        assert_eq!(main_view.measure_text_widths().units(), 0);
+    }
+
+    #[test]
+    fn font_entries_load_visible_fonts_once() {
+        let font_entries = FontListRepository::default().find_all();
+        let mut main_view = create_main_view();
+
+        let task = main_view.update(MainViewCommand::XMessage(XMessage::FontEntries(
+            font_entries.clone(),
+        )));
+        assert_eq!(task.units(), font_entries.len());
+        assert!(
+            main_view
+                .requested_fonts
+                .contains(font_entries[0].font_name)
+        );
+
+        let task = main_view.update(MainViewCommand::Scrolled(0.0));
+        assert_eq!(task.units(), 0);
+    }
+
     #[test]
     #[ignore = "snapshot testing"]
     fn test_simulator() {
         let font_entries = FontListRepository::default().find_all();
-        let mut main_view = MainView::new();
+        let mut main_view = create_main_view();
         let _ = main_view.update(MainViewCommand::XMessage(XMessage::FontEntries(
             font_entries,
         )));
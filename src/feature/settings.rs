@@ -17,4 +17,5 @@ pub use settings_view::{SettingsView, SettingsViewCommand};
 
 mod checkable_font_list_item;
 mod font_list_item;
+mod prefs_history;
 mod settings_view;
@@ -15,11 +15,15 @@
  */
 use crate::feature::settings::SettingsViewCommand;
 use crate::feature::settings::font_list_item::FontListItem;
-use iced::Element;
-use iced::widget::{checkbox, row};
+use iced::widget::{button, checkbox, row, text};
+use iced::{Alignment, Element};
 
+/// Check box of a font and the star marking it as a favourite. The check box is read-only if
+/// not `editable`, e.g. while the fonts come from rules.
 pub fn checkable_font_list_item(
     font_list_item: &'_ FontListItem,
+    favourite: bool,
+    editable: bool,
 ) -> Element<'_, SettingsViewCommand> {
     let label = font_list_item
         .font_entry
@@ -28,11 +32,25 @@ pub fn checkable_font_list_item(
         .unwrap_or(font_list_item.font_entry.font_name);
     let font_entry = font_list_item.font_entry.clone();
     row![
+        button(text(if favourite { "\u{2605}" } else { "\u{2606}" }).size(12))
+            .padding(0)
+            .style(button::text)
+            .on_press(SettingsViewCommand::FontFavouriteToggled(
+                font_entry.id.clone(),
+                !favourite,
+            )),
         checkbox(font_list_item.enabled)
             .label(label)
-            .on_toggle(move |enabled| {
+            .on_toggle_maybe(editable.then_some(move |enabled| {
                 SettingsViewCommand::FontListItemChanged(font_entry.clone(), enabled)
-            }),
+            })),
     ]
+    .push(
+        font_list_item
+            .is_new
+            .then(|| text("NEW").size(10).style(text::primary)),
+    )
+    .spacing(4)
+    .align_y(Alignment::Center)
     .into()
 }
@@ -18,4 +18,6 @@ use crate::model::FontEntry;
 pub struct FontListItem {
     pub enabled: bool,
     pub font_entry: FontEntry,
+    /// Added to the catalog since the last run.
+    pub is_new: bool,
 }
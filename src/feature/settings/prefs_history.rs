@@ -0,0 +1,135 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::Preferences;
+
+/// Number of undo steps kept.
+const PREFS_HISTORY_LIMIT: usize = 100;
+
+/// Preferences with the preset selected at the time.
+#[derive(Clone, Debug)]
+pub struct PrefsSnapshot {
+    pub prefs: Preferences,
+    pub selected_name: Option<String>,
+}
+
+impl PrefsSnapshot {
+    /// Whether the user-visible settings are the same. The sample text history, the favourites
+    /// and the catalog version are kept as they are when undoing, so they are not compared.
+    fn is_same_edit(&self, other: &PrefsSnapshot) -> bool {
+        self.prefs.presets == other.prefs.presets
+            && self.prefs.font_cache_budget_mb == other.prefs.font_cache_budget_mb
+    }
+}
+
+/// Undo and redo history of [`Preferences`].
+#[derive(Default)]
+pub struct PrefsHistory {
+    undo: Vec<PrefsSnapshot>,
+    redo: Vec<PrefsSnapshot>,
+    current: Option<PrefsSnapshot>,
+    /// Group of the last recorded step. Steps of the same group are merged into one, e.g. the
+    /// keystrokes of a text.
+    current_group: Option<&'static str>,
+}
+
+impl PrefsHistory {
+    /// Forgets the history and starts it from `snapshot`.
+    pub fn reset(&mut self, snapshot: PrefsSnapshot) {
+        self.undo.clear();
+        self.redo.clear();
+        self.current = Some(snapshot);
+        self.current_group = None;
+    }
+
+    /// Records `snapshot` as a step after the current one, unless nothing that can be undone
+    /// has changed.
+    pub fn record(&mut self, snapshot: PrefsSnapshot, group: Option<&'static str>) {
+        let Some(current) = self.current.replace(snapshot.clone()) else {
+            return;
+        };
+        let merged = group.is_some() && group == self.current_group;
+        self.current_group = group;
+        if current.is_same_edit(&snapshot) {
+            return;
+        }
+
+        self.redo.clear();
+        if !merged {
+            self.undo.push(current);
+            if self.undo.len() > PREFS_HISTORY_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+    }
+
+    /// Returns the snapshot to restore, which becomes the current one.
+    pub fn undo(&mut self) -> Option<PrefsSnapshot> {
+        let snapshot = self.undo.pop()?;
+        self.redo.extend(self.current.replace(snapshot.clone()));
+        self.current_group = None;
+        Some(snapshot)
+    }
+
+    /// Returns the snapshot to restore, which becomes the current one.
+    pub fn redo(&mut self) -> Option<PrefsSnapshot> {
+        let snapshot = self.redo.pop()?;
+        self.undo.extend(self.current.replace(snapshot.clone()));
+        self.current_group = None;
+        Some(snapshot)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(budget_mb: u32) -> PrefsSnapshot {
+        PrefsSnapshot {
+            prefs: Preferences {
+                font_cache_budget_mb: budget_mb,
+                ..Preferences::default()
+            },
+            selected_name: None,
+        }
+    }
+
+    #[test]
+    fn steps_of_the_same_group_are_merged() {
+        let mut history = PrefsHistory::default();
+        history.reset(snapshot(1));
+        history.record(snapshot(2), Some("text"));
+        history.record(snapshot(3), Some("text"));
+        history.record(snapshot(3), None);
+        history.record(snapshot(4), None);
+
+        assert_eq!(history.undo().unwrap().prefs.font_cache_budget_mb, 3);
+        assert_eq!(history.undo().unwrap().prefs.font_cache_budget_mb, 1);
+        assert!(!history.can_undo());
+        assert_eq!(history.redo().unwrap().prefs.font_cache_budget_mb, 3);
+
+        history.record(snapshot(5), None);
+        assert!(!history.can_redo());
+    }
+}
@@ -14,128 +14,362 @@
  * limitations under the License.
  */
 use crate::asset::Asset;
+use crate::data::font_file::FontFileRepository;
 use crate::data::font_list::FontListRepository;
-use crate::data::preferences::PreferencesRepository;
+use crate::data::preferences::{PreferencesRepository, decode_share_code, encode_share_code};
+use crate::data::sample_text::SampleTextRepository;
+use crate::export::{
+    PresetExport, SpecimenFont, export_font_subsets, export_html_specimen, export_pdf_specimen,
+    read_font_facts, unique_path,
+};
 use crate::feature::settings::checkable_font_list_item::checkable_font_list_item;
 use crate::feature::settings::font_list_item::FontListItem;
+use crate::feature::settings::prefs_history::{PrefsHistory, PrefsSnapshot};
 use crate::model::{
-    DEFAULT_SAMPLE_FONT_SIZE, FontEntry, Preferences, Preset, SAVE_PREFS_DEBOUNCE_MILLIS,
-    TOOLBAR_HEIGHT, WINDOW_BODY_MARGIN, XMessage,
+    DEFAULT_FONT_CACHE_BUDGET_MB, DEFAULT_SAMPLE_TEXT, ExportResult, ExportStatus, FontCacheStatus,
+    FontCatalog, FontEntry, FontFacts, FontRule, FontRuleKind, FontSelectionMode, Preferences,
+    Preset, PresetDiff, PresetFileFormat, PresetSetOperation, PreviewLayout, PreviewLayoutKind,
+    SAVE_PREFS_DEBOUNCE_MILLIS, SampleText, SortMode, SpecimenRowKind, SpecimenTemplate,
+    StaleEntry, TOOLBAR_HEIGHT, WINDOW_BODY_MARGIN, XMessage, find_matching_fonts,
 };
 use crate::prelude::*;
-use crate::widget::settings_button_solid;
+use crate::widget::{drop_target_style, settings_button_solid};
 use iced::widget::container::background;
 use iced::widget::operation;
 use iced::widget::rule::horizontal;
 use iced::widget::{
-    button, column, container, radio, row, scrollable, space, svg, text_editor, text_input,
+    button, checkbox, column, container, mouse_area, pick_list, radio, row, scrollable, space, svg,
+    text, text_editor, text_input,
+};
+use iced::{
+    Alignment, Color, Element, Font, Length, Subscription, Task, Theme, keyboard, mouse, padding,
 };
-use iced::{Alignment, Color, Element, Length, Subscription, Task, Theme, padding};
 use iced_aw::number_input;
 use indexmap::IndexMap;
-use std::collections::HashSet;
-use std::sync::{Arc, LazyLock};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Interval of polling the pack directory for added, removed or changed files. Every poll stats
+/// the whole pack, so it is kept long.
+const PACK_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Facts of the fonts read for rules, per font name. `None` if the file could not be read.
+type FontFactsCache = HashMap<&'static str, Option<Arc<FontFacts>>>;
 
 static RENAME_INPUT_ID: LazyLock<iced::widget::Id> =
     LazyLock::new(|| iced::widget::Id::from("preset-rename-input"));
 
 #[derive(Clone, Debug)]
 pub enum SettingsViewCommand {
+    BuiltinPresetCopyClicked(String),
+    ExportFinished(Result<ExportResult, String>),
+    ExportHtmlClicked,
+    ExportPdfClicked,
+    ExportSubsetClicked,
+    FitToWidthToggled(bool),
+    FontCatalogReloaded(FontCatalog),
+    FontCacheBudgetUpdated(u32),
+    FontCachePanelToggled(bool),
+    FontCacheRefreshTick,
+    FontFavouriteToggled(String, bool),
     FontListItemChanged(FontEntry, bool),
-    FontSizeUpdated(u32),
+    FontSelectionModeSelected(FontSelectionMode),
+    GridColumnsUpdated(u32),
+    LayoutKindSelected(PreviewLayoutKind),
+    PackPollTick,
+    PackScanned(Option<u64>),
     PrefsLoaded(Preferences),
     PresetAddClicked,
+    PresetCodeCopyClicked,
+    PresetCodePasteClicked,
+    PresetCodePasted(Option<String>),
     PresetCopyClicked(String),
     PresetDeleteClicked(String),
+    PresetDiffToggled,
+    PresetDragEnded,
+    PresetDragHovered(String),
+    PresetDragStarted(String),
+    PresetExportAllClicked,
+    PresetExportClicked,
+    PresetExportFinished(Result<PathBuf, String>),
+    PresetFileChanged(String),
+    PresetFileFormatSelected(PresetFileFormat),
+    PresetImportClicked,
+    PresetImportFinished(Result<Vec<Preset>, String>),
     PresetMoveDown(String),
     PresetMoveUp(String),
+    PresetOperandASelected(String),
+    PresetOperandBSelected(String),
     PresetRenameStarted(String),
     PresetRenameChanged(String),
     PresetRenameConfirmed,
     PresetSelected(String),
+    PresetSetOperationClicked(PresetSetOperation),
+    RedoClicked,
+    RuleAdded(FontRuleKind),
+    RuleChanged(usize, FontRule),
+    RuleRemoved(usize),
+    RulesEvaluated(Vec<FontRule>, Vec<String>),
+    SampleTextHistorySelected(SampleTextHistoryItem),
+    SampleTextNameChanged(String),
+    SampleTextRemoveClicked,
+    SampleTextSaveClicked,
+    SampleTextSelected(SampleText),
+    SampleTextsLoaded(Vec<SampleText>),
     SavePrefsRequested(u64),
     SettingsButtonClicked,
+    SortModeSelected(SortMode),
+    SpecimenRowSizeUpdated(SpecimenRowKind, u32),
+    SpecimenRowToggled(SpecimenRowKind, bool),
+    StaleEntriesCleanupClicked,
+    StaleEntryRemapClicked(StaleEntryWarning),
+    SubsetTextFileChanged(String),
     TextEditorAction(text_editor::Action),
+    UndoClicked,
     SendXMessage(XMessage),
     Sink,
     XMessage(XMessage),
 }
 
 pub struct SettingsView {
+    /// Read-only presets shipped with the app, only used as sources of copies.
+    builtin_presets: Vec<Preset>,
     custom_text_content: text_editor::Content,
-    font_size: u32,
+    export_dir: PathBuf,
+    export_status: Option<ExportStatus>,
+    font_cache_panel_visible: bool,
+    font_cache_status: FontCacheStatus,
+    font_facts: Arc<Mutex<FontFactsCache>>,
+    font_file_repo: Arc<FontFileRepository>,
     font_list_item_map: IndexMap<String, FontListItem>,
+    font_list_repo: Arc<FontListRepository>,
+    /// Fonts added to the catalog since the last run.
+    new_font_ids: HashSet<String>,
+    /// Last [`FontListRepository::pack_signature`]. The pack is only polled if it is `Some`.
+    pack_signature: Option<u64>,
     prefs: Option<Preferences>,
     save_prefs_version: u64,
     prefs_repo: Arc<PreferencesRepository>,
+    prefs_history: PrefsHistory,
+    preset_drag: Option<PresetDrag>,
     prefs_selected_name: Option<String>,
     editing_preset: Option<EditingPreset>,
+    /// Path of a preset file to import.
+    preset_file: String,
+    preset_file_format: PresetFileFormat,
+    preset_file_status: Option<String>,
+    /// Names of the presets to combine or compare.
+    preset_operands: (Option<String>, Option<String>),
+    preset_diff_visible: bool,
+    sample_text_name: String,
+    sample_text_repo: Arc<SampleTextRepository>,
+    sample_texts: Vec<SampleText>,
+    /// Whether the settings are shown. The undo shortcuts only apply while they are.
+    settings_open: bool,
+    sort_mode: SortMode,
+    /// Preset entries that do not match the catalog, refreshed by [`Self::validate_presets`].
+    stale_entries: Vec<StaleEntryWarning>,
+    /// Fingerprint of the preset ID lists [`Self::stale_entries`] were found in. `None` after the
+    /// catalog changed.
+    stale_entries_key: Option<u64>,
+    /// Suggested catalog entry per orphaned ID, kept until the catalog changes.
+    similar_font_ids: HashMap<String, Option<String>>,
+    layout: PreviewLayout,
+    subset_text_file: String,
+    template: SpecimenTemplate,
 }
 
 impl SettingsView {
     pub fn new(
         font_list_repo: Arc<FontListRepository>,
+        font_file_repo: Arc<FontFileRepository>,
         prefs_repo: Arc<PreferencesRepository>,
+        sample_text_repo: Arc<SampleTextRepository>,
+        export_dir: PathBuf,
     ) -> Self {
         let font_entries = font_list_repo.find_all();
         let mut font_list_item_map = IndexMap::with_capacity(font_entries.len());
         for font_entry in font_entries {
             font_list_item_map.insert(
-                font_entry.filepath.to_owned(),
+                font_entry.id.to_owned(),
                 FontListItem {
                     enabled: true,
                     font_entry,
+                    is_new: false,
                 },
             );
         }
 
         Self {
+            builtin_presets: prefs_repo.find_builtin_presets(),
             custom_text_content: text_editor::Content::new(),
-            font_size: DEFAULT_SAMPLE_FONT_SIZE,
+            export_dir,
+            export_status: None,
+            font_cache_panel_visible: false,
+            font_cache_status: FontCacheStatus::default(),
+            font_facts: Arc::new(Mutex::new(HashMap::new())),
+            font_file_repo,
             font_list_item_map,
+            font_list_repo,
+            new_font_ids: HashSet::new(),
+            pack_signature: None,
             prefs: None,
             save_prefs_version: 0,
             prefs_repo,
+            prefs_history: PrefsHistory::default(),
+            preset_drag: None,
             prefs_selected_name: None,
             editing_preset: None,
+            preset_file: String::new(),
+            preset_file_format: PresetFileFormat::default(),
+            preset_file_status: None,
+            preset_operands: (None, None),
+            preset_diff_visible: false,
+            sample_text_name: String::new(),
+            sample_text_repo,
+            sample_texts: vec![],
+            settings_open: false,
+            sort_mode: SortMode::default(),
+            stale_entries: vec![],
+            stale_entries_key: None,
+            similar_font_ids: HashMap::new(),
+            layout: PreviewLayout::default(),
+            subset_text_file: String::new(),
+            template: SpecimenTemplate::default(),
         }
     }
 
     pub fn update(&mut self, command: SettingsViewCommand) -> Task<SettingsViewCommand> {
         match command {
+            SettingsViewCommand::BuiltinPresetCopyClicked(name) => {
+                let Some(source) = self
+                    .builtin_presets
+                    .iter()
+                    .find(|preset| preset.name == name)
+                    .cloned()
+                else {
+                    return Task::none();
+                };
+                let index = self.prefs.as_ref().map_or(0, |prefs| prefs.presets.len());
+                self.copy_preset(source, index)
+            }
+            SettingsViewCommand::ExportFinished(result) => {
+                self.export_status = Some(result.into());
+                Task::none()
+            }
+            SettingsViewCommand::ExportHtmlClicked => self.start_export(|dir, preset, _| {
+                export_html_specimen(dir, preset).map(ExportResult::from)
+            }),
+            SettingsViewCommand::ExportPdfClicked => {
+                self.start_export(|dir, preset, font_file_repo| {
+                    export_pdf_specimen(dir, preset, font_file_repo.find_label_font().as_ref())
+                        .map(ExportResult::from)
+                })
+            }
+            SettingsViewCommand::ExportSubsetClicked => {
+                let text_file = Some(self.subset_text_file.trim())
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from);
+                self.start_export(move |dir, preset, _| {
+                    let text = match text_file {
+                        Some(path) => std::fs::read_to_string(&path)
+                            .with_context(|| format!("failed to read {}", path.display()))?,
+                        None => preset.custom_text.clone(),
+                    };
+                    export_font_subsets(dir, preset, &text)
+                })
+            }
+            SettingsViewCommand::FitToWidthToggled(enabled) => {
+                self.update_template(|template| template.fit_to_width = enabled)
+            }
+            SettingsViewCommand::FontCatalogReloaded(catalog) => {
+                send_xmessage(XMessage::FontCatalog(catalog))
+            }
+            SettingsViewCommand::FontCacheBudgetUpdated(budget_mb) => {
+                let Some(prefs) = self.prefs.as_mut() else {
+                    return Task::none();
+                };
+                prefs.font_cache_budget_mb = budget_mb;
+                self.font_list_repo
+                    .set_font_cache_budget(mib_to_bytes(budget_mb));
+                self.font_cache_status = self.font_list_repo.font_cache_status();
+                self.schedule_save_prefs()
+            }
+            SettingsViewCommand::FontCachePanelToggled(visible) => {
+                self.font_cache_panel_visible = visible;
+                self.font_cache_status = self.font_list_repo.font_cache_status();
+                Task::none()
+            }
+            SettingsViewCommand::FontCacheRefreshTick => {
+                self.font_cache_status = self.font_list_repo.font_cache_status();
+                Task::none()
+            }
+            SettingsViewCommand::FontFavouriteToggled(id, favourite) => {
+                let Some(prefs) = self.prefs.as_mut() else {
+                    return Task::none();
+                };
+                prefs.favourite_ids.retain(|data| data != &id);
+                if favourite {
+                    prefs.favourite_ids.push(id);
+                }
+                Task::batch([self.evaluate_rules(), self.schedule_save_prefs()])
+            }
             SettingsViewCommand::FontListItemChanged(font_entry, enabled) => {
                 let font_list_item = self
                     .font_list_item_map
-                    .get_mut(&font_entry.filepath)
+                    .get_mut(&font_entry.id)
                     .expect("SettingsView should have entry");
                 font_list_item.enabled = enabled;
 
                 if let Some(preset) = self.selected_preset_mut() {
-                    if enabled {
-                        preset.enable_paths.push(font_entry.filepath.clone());
-                    } else {
-                        preset
-                            .enable_paths
-                            .retain(|path| path != &font_entry.filepath);
-                    }
+                    preset.set_font_enabled(&font_entry.id, enabled);
                 }
+                self.validate_presets();
 
                 Task::batch([
                     send_xmessage(XMessage::FontEntries(self.create_font_entries())),
                     self.schedule_save_prefs(),
                 ])
             }
-            SettingsViewCommand::FontSizeUpdated(size) => {
-                self.font_size = size;
-
-                if let Some(preset) = self.selected_preset_mut() {
-                    preset.font_size = size;
+            SettingsViewCommand::FontSelectionModeSelected(mode) => {
+                let enabled_ids = self
+                    .font_list_item_map
+                    .values()
+                    .filter(|item| item.enabled)
+                    .map(|item| item.font_entry.id.clone())
+                    .collect::<Vec<_>>();
+                let disabled_ids = self
+                    .font_list_item_map
+                    .values()
+                    .filter(|item| !item.enabled)
+                    .map(|item| item.font_entry.id.clone())
+                    .collect::<Vec<_>>();
+                let Some(preset) = self.selected_preset_mut() else {
+                    return Task::none();
+                };
+                if preset.font_selection_mode == mode {
+                    return Task::none();
                 }
 
-                Task::batch([
-                    send_xmessage(XMessage::FontSize(size)),
-                    self.schedule_save_prefs(),
-                ])
+                // Store the current selection in the list of the new mode so that nothing
+                // changes on screen.
+                preset.font_selection_mode = mode;
+                (preset.enabled_ids, preset.disabled_ids) = match mode {
+                    FontSelectionMode::AllowList => (enabled_ids, vec![]),
+                    FontSelectionMode::DenyList => (vec![], disabled_ids),
+                    FontSelectionMode::Rules => (vec![], vec![]),
+                };
+                self.validate_presets();
+                Task::batch([self.evaluate_rules(), self.schedule_save_prefs()])
+            }
+            SettingsViewCommand::GridColumnsUpdated(columns) => {
+                self.update_layout(|layout| layout.grid_columns = columns)
+            }
+            SettingsViewCommand::LayoutKindSelected(kind) => {
+                self.update_layout(|layout| layout.kind = kind)
             }
             SettingsViewCommand::PresetAddClicked => {
                 let Some(prefs) = self.prefs.as_mut() else {
@@ -145,8 +379,8 @@ impl SettingsView {
                 let new_name = next_preset_name(&prefs.presets);
                 let new_preset = Preset {
                     name: new_name.clone(),
-                    font_size: DEFAULT_SAMPLE_FONT_SIZE,
-                    enable_paths: self.font_list_item_map.keys().cloned().collect(),
+                    enabled_ids: self.font_list_item_map.keys().cloned().collect(),
+                    ..Default::default()
                 };
                 prefs.presets.push(new_preset.clone());
                 self.prefs_selected_name = Some(new_name);
@@ -154,25 +388,89 @@ impl SettingsView {
 
                 Task::batch([self.notify_preset_applied(), self.schedule_save_prefs()])
             }
+            SettingsViewCommand::PresetCodeCopyClicked => {
+                let Some(mut preset) = self.selected_preset().cloned() else {
+                    return Task::none();
+                };
+                // Codes always list the shown fonts, whichever list the preset stores, so IDs
+                // missing from the catalog are left out.
+                let unknown_ids = preset
+                    .enabled_ids
+                    .iter()
+                    .filter(|id| !self.font_list_item_map.contains_key(*id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                preset.enabled_ids = self
+                    .create_font_entries()
+                    .iter()
+                    .map(|entry| entry.id.clone())
+                    .collect();
+                match encode_share_code(&preset) {
+                    Ok(code) => {
+                        let name = &preset.name;
+                        self.preset_file_status = Some(if unknown_ids.is_empty() {
+                            format!("Copied the code of {name} to the clipboard")
+                        } else {
+                            format!(
+                                "Copied the code of {name} to the clipboard without {}",
+                                describe_unknown_fonts(&unknown_ids)
+                            )
+                        });
+                        iced::clipboard::write(code)
+                    }
+                    Err(e) => {
+                        warn!(?e, "failed to encode preset");
+                        self.preset_file_status = Some(format!("Copy failed: {e:#}"));
+                        Task::none()
+                    }
+                }
+            }
+            SettingsViewCommand::PresetCodePasteClicked => {
+                iced::clipboard::read().map(SettingsViewCommand::PresetCodePasted)
+            }
+            SettingsViewCommand::PresetCodePasted(code) => {
+                let preset = match decode_share_code(code.as_deref().unwrap_or_default()) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        self.preset_file_status = Some(format!("Paste failed: {e:#}"));
+                        return Task::none();
+                    }
+                };
+
+                let unknown_ids = preset
+                    .enabled_ids
+                    .iter()
+                    .filter(|id| !self.font_list_item_map.contains_key(*id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                self.add_presets(vec![preset]);
+                let Some(name) = self
+                    .prefs
+                    .as_ref()
+                    .and_then(|prefs| prefs.presets.last())
+                    .map(|preset| preset.name.clone())
+                else {
+                    return Task::none();
+                };
+                self.preset_file_status = Some(if unknown_ids.is_empty() {
+                    format!("Pasted {name}")
+                } else {
+                    format!(
+                        "Pasted {name} with {}",
+                        describe_unknown_fonts(&unknown_ids)
+                    )
+                });
+                self.schedule_save_prefs()
+            }
             SettingsViewCommand::PresetCopyClicked(name) => {
-                let Some(prefs) = self.prefs.as_mut() else {
+                let Some(prefs) = self.prefs.as_ref() else {
                     return Task::none();
                 };
                 let Some(index) = prefs.presets.iter().position(|p| p.name == name) else {
                     return Task::none();
                 };
                 let source = prefs.presets[index].clone();
-                let new_name = next_copy_name(&source.name, &prefs.presets);
-                let new_preset = Preset {
-                    name: new_name.clone(),
-                    font_size: source.font_size,
-                    enable_paths: source.enable_paths.clone(),
-                };
-                prefs.presets.insert(index + 1, new_preset.clone());
-                self.prefs_selected_name = Some(new_name);
-                self.apply_preset(&new_preset);
-
-                Task::batch([self.notify_preset_applied(), self.schedule_save_prefs()])
+                self.copy_preset(source, index + 1)
             }
             SettingsViewCommand::PresetMoveUp(name) => self.move_preset(&name, -1),
             SettingsViewCommand::PresetMoveDown(name) => self.move_preset(&name, 1),
@@ -197,12 +495,126 @@ impl SettingsView {
                     let new_preset = prefs.presets[new_index].clone();
                     self.prefs_selected_name = Some(new_preset.name.clone());
                     self.apply_preset(&new_preset);
+                    self.validate_presets();
 
                     Task::batch([self.notify_preset_applied(), self.schedule_save_prefs()])
                 } else {
+                    self.validate_presets();
                     self.schedule_save_prefs()
                 }
             }
+            SettingsViewCommand::PresetExportAllClicked => self.export_presets(true),
+            SettingsViewCommand::PresetExportClicked => self.export_presets(false),
+            SettingsViewCommand::PresetExportFinished(result) => {
+                self.preset_file_status =
+                    Some(ExportStatus::from(result.map(ExportResult::from)).to_string());
+                Task::none()
+            }
+            SettingsViewCommand::PresetFileChanged(path) => {
+                self.preset_file = path;
+                Task::none()
+            }
+            SettingsViewCommand::PresetFileFormatSelected(format) => {
+                self.preset_file_format = format;
+                Task::none()
+            }
+            SettingsViewCommand::PresetImportClicked => {
+                let path = PathBuf::from(self.preset_file.trim());
+                if path.as_os_str().is_empty() {
+                    return Task::none();
+                }
+
+                let prefs_repo = self.prefs_repo.clone();
+                Task::perform(
+                    async move { prefs_repo.read_preset_file(&path) },
+                    |result| {
+                        if let Err(e) = &result {
+                            warn!(?e, "failed to import presets");
+                        }
+                        SettingsViewCommand::PresetImportFinished(
+                            result.map_err(|e| format!("{e:#}")),
+                        )
+                    },
+                )
+            }
+            SettingsViewCommand::PresetImportFinished(result) => {
+                let presets = match result {
+                    Ok(data) => data,
+                    Err(e) => {
+                        self.preset_file_status = Some(format!("Import failed: {e}"));
+                        return Task::none();
+                    }
+                };
+                let count = presets.len();
+                self.add_presets(presets);
+                self.preset_file_status = Some(format!("Imported {count} presets"));
+                self.schedule_save_prefs()
+            }
+            SettingsViewCommand::PresetDiffToggled => {
+                self.preset_diff_visible = !self.preset_diff_visible;
+                Task::none()
+            }
+            SettingsViewCommand::PresetDragEnded => {
+                let Some(PresetDrag {
+                    name,
+                    target: Some(target),
+                }) = self.preset_drag.take()
+                else {
+                    return Task::none();
+                };
+                let Some(new_index) = self
+                    .prefs
+                    .as_ref()
+                    .and_then(|prefs| prefs.presets.iter().position(|p| p.name == target))
+                else {
+                    return Task::none();
+                };
+                self.move_preset_to(&name, new_index)
+            }
+            SettingsViewCommand::PresetDragHovered(name) => {
+                if let Some(drag) = self.preset_drag.as_mut() {
+                    drag.target = Some(name);
+                }
+                Task::none()
+            }
+            SettingsViewCommand::PresetDragStarted(name) => {
+                self.preset_drag = Some(PresetDrag { name, target: None });
+                Task::none()
+            }
+            SettingsViewCommand::PresetOperandASelected(name) => {
+                self.preset_operands.0 = Some(name);
+                Task::none()
+            }
+            SettingsViewCommand::PresetOperandBSelected(name) => {
+                self.preset_operands.1 = Some(name);
+                Task::none()
+            }
+            SettingsViewCommand::PresetSetOperationClicked(operation) => {
+                let Some((a, b)) = self.preset_operands() else {
+                    return Task::none();
+                };
+                let enabled_ids =
+                    operation.apply(&self.preset_font_ids(a), &self.preset_font_ids(b));
+                let name = format!("{} {} {}", a.name, operation.symbol(), b.name);
+                let new_preset = Preset {
+                    name,
+                    enabled_ids,
+                    font_selection_mode: FontSelectionMode::AllowList,
+                    ..a.clone()
+                };
+
+                self.remember_custom_text();
+                self.add_presets(vec![new_preset]);
+                let Some(new_preset) = self.prefs.as_ref().and_then(|prefs| prefs.presets.last())
+                else {
+                    return Task::none();
+                };
+                let new_preset = new_preset.clone();
+                self.prefs_selected_name = Some(new_preset.name.clone());
+                self.apply_preset(&new_preset);
+
+                Task::batch([self.notify_preset_applied(), self.schedule_save_prefs()])
+            }
             SettingsViewCommand::PresetRenameStarted(name) => {
                 self.editing_preset = Some(EditingPreset {
                     original_name: name.clone(),
@@ -242,9 +654,16 @@ impl SettingsView {
                     preset.name = new_name.clone();
                 }
 
-                if self.prefs_selected_name.as_ref() == Some(&editing.original_name) {
-                    self.prefs_selected_name = Some(new_name);
+                for name in [
+                    &mut self.prefs_selected_name,
+                    &mut self.preset_operands.0,
+                    &mut self.preset_operands.1,
+                ] {
+                    if name.as_ref() == Some(&editing.original_name) {
+                        *name = Some(new_name.clone());
+                    }
                 }
+                self.validate_presets();
 
                 self.schedule_save_prefs()
             }
@@ -253,6 +672,7 @@ impl SettingsView {
                     return Task::none();
                 }
 
+                self.remember_custom_text();
                 self.prefs_selected_name = Some(name.clone());
 
                 if let Some(preset) = self
@@ -266,12 +686,33 @@ impl SettingsView {
 
                 Task::batch([self.notify_preset_applied(), self.schedule_save_prefs()])
             }
+            SettingsViewCommand::PackPollTick => {
+                let font_list_repo = self.font_list_repo.clone();
+                Task::perform(
+                    async move { font_list_repo.pack_signature() },
+                    SettingsViewCommand::PackScanned,
+                )
+            }
+            SettingsViewCommand::PackScanned(signature) => {
+                let changed = self.pack_signature.is_some() && self.pack_signature != signature;
+                self.pack_signature = signature;
+                if !changed {
+                    return Task::none();
+                }
+
+                debug!("pack directory changed");
+                let font_list_repo = self.font_list_repo.clone();
+                Task::perform(
+                    async move { font_list_repo.reload() },
+                    SettingsViewCommand::FontCatalogReloaded,
+                )
+            }
             SettingsViewCommand::PrefsLoaded(mut prefs) => {
                 if prefs.presets.is_empty() {
                     prefs.presets.push(Preset {
                         name: "Preset 1".into(),
-                        font_size: DEFAULT_SAMPLE_FONT_SIZE,
-                        enable_paths: self.font_list_item_map.keys().cloned().collect(),
+                        enabled_ids: self.font_list_item_map.keys().cloned().collect(),
+                        ..Default::default()
                     });
                 }
 
@@ -286,9 +727,127 @@ impl SettingsView {
                     self.apply_preset(preset);
                 }
 
+                self.font_list_repo
+                    .set_font_cache_budget(mib_to_bytes(prefs.font_cache_budget_mb));
+
+                let catalog_version = self.font_list_repo.catalog_version();
+                if let Some(version) = prefs.catalog_version
+                    && version < catalog_version
+                {
+                    self.new_font_ids = self
+                        .font_list_repo
+                        .find_ids_added_since(version)
+                        .into_iter()
+                        .collect();
+                    for item in self.font_list_item_map.values_mut() {
+                        item.is_new = self.new_font_ids.contains(&item.font_entry.id);
+                    }
+                }
+                let catalog_version_changed = prefs.catalog_version != Some(catalog_version);
+                prefs.catalog_version = Some(catalog_version);
                 self.prefs = Some(prefs);
+                self.validate_presets();
+                if let Some(snapshot) = self.prefs_snapshot() {
+                    self.prefs_history.reset(snapshot);
+                }
+
+                // Saved right away rather than marked dirty, so that the fonts are only reported
+                // as new once even if nothing else is changed.
+                Task::batch([
+                    self.notify_preset_applied(),
+                    if catalog_version_changed {
+                        self.save_prefs()
+                    } else {
+                        Task::none()
+                    },
+                ])
+            }
+            SettingsViewCommand::RedoClicked => {
+                let snapshot = self.prefs_history.redo();
+                self.restore_prefs(snapshot)
+            }
+            SettingsViewCommand::RuleAdded(kind) => {
+                self.update_rules(|rules| rules.push(kind.default_rule()))
+            }
+            SettingsViewCommand::RuleChanged(index, rule) => self.update_rules(|rules| {
+                if let Some(data) = rules.get_mut(index) {
+                    *data = rule;
+                }
+            }),
+            SettingsViewCommand::RuleRemoved(index) => self.update_rules(|rules| {
+                if index < rules.len() {
+                    rules.remove(index);
+                }
+            }),
+            SettingsViewCommand::RulesEvaluated(rules, ids) => {
+                // Drop results of rules that have been edited or left in the meantime.
+                if !self.selected_preset().is_some_and(|preset| {
+                    preset.font_selection_mode == FontSelectionMode::Rules && preset.rules == rules
+                }) {
+                    return Task::none();
+                }
+
+                let ids = ids.into_iter().collect::<HashSet<_>>();
+                for (id, item) in self.font_list_item_map.iter_mut() {
+                    item.enabled = ids.contains(id);
+                }
+                send_xmessage(XMessage::FontEntries(self.create_font_entries()))
+            }
+            SettingsViewCommand::SampleTextHistorySelected(item) => {
+                self.custom_text_content = text_editor::Content::with_text(&item.0);
+                self.update_custom_text()
+            }
+            SettingsViewCommand::SampleTextNameChanged(name) => {
+                self.sample_text_name = name;
+                Task::none()
+            }
+            SettingsViewCommand::SampleTextRemoveClicked => {
+                let name = self.sample_text_name.trim();
+                let len = self.sample_texts.len();
+                self.sample_texts
+                    .retain(|entry| !entry.user_defined || entry.name != name);
+                if self.sample_texts.len() == len {
+                    return Task::none();
+                }
+
+                self.sample_text_name.clear();
+                self.save_user_defined_sample_texts()
+            }
+            SettingsViewCommand::SampleTextSaveClicked => {
+                let name = self.sample_text_name.trim().to_string();
+                let text = self.custom_text_content.text().trim_end().to_string();
+                if name.is_empty() || text.is_empty() {
+                    return Task::none();
+                }
+
+                let entry = SampleText {
+                    name,
+                    text,
+                    user_defined: true,
+                };
+                match self
+                    .sample_texts
+                    .iter_mut()
+                    .find(|data| data.user_defined && data.name == entry.name)
+                {
+                    Some(data) => *data = entry,
+                    None => self.sample_texts.push(entry),
+                }
 
-                self.notify_preset_applied()
+                self.save_user_defined_sample_texts()
+            }
+            SettingsViewCommand::SampleTextSelected(entry) => {
+                self.sample_text_name = if entry.user_defined {
+                    entry.name
+                } else {
+                    String::new()
+                };
+                self.custom_text_content = text_editor::Content::with_text(&entry.text);
+                self.update_custom_text()
+            }
+            SettingsViewCommand::SampleTextsLoaded(entries) => {
+                self.sample_texts = entries;
+                Task::none()
             }
             SettingsViewCommand::SavePrefsRequested(version) => {
                 if version != self.save_prefs_version {
@@ -296,33 +855,84 @@ impl SettingsView {
                 }
                 // Reset to 0 to indicate no pending unsaved changes.
                 self.save_prefs_version = 0;
+                self.remember_custom_text();
+                self.save_prefs()
+            }
+            SettingsViewCommand::SettingsButtonClicked => send_xmessage(XMessage::SettingsClose),
+            SettingsViewCommand::SortModeSelected(sort_mode) => {
+                self.sort_mode = sort_mode;
+
+                if let Some(preset) = self.selected_preset_mut() {
+                    preset.sort_mode = sort_mode;
+                }
 
-                let Some(prefs) = self.prefs.clone() else {
+                Task::batch([
+                    send_xmessage(XMessage::SortMode(sort_mode)),
+                    self.schedule_save_prefs(),
+                ])
+            }
+            SettingsViewCommand::SpecimenRowSizeUpdated(kind, size) => {
+                self.update_template(|template| template.set_font_size(kind, size))
+            }
+            SettingsViewCommand::SpecimenRowToggled(kind, enabled) => {
+                self.update_template(|template| template.set_enabled(kind, enabled))
+            }
+            SettingsViewCommand::StaleEntriesCleanupClicked => {
+                let Some(prefs) = self.prefs.as_mut() else {
                     return Task::none();
                 };
-                let prefs_repo = self.prefs_repo.clone();
-                Task::perform(
-                    async move {
-                        match prefs_repo.save(prefs) {
-                            Ok(_) => info!("saved preferences"),
-                            Err(e) => warn!(?e, "failed to save preferences"),
-                        };
-                    },
-                    |_| SettingsViewCommand::Sink,
-                )
+                for preset in &mut prefs.presets {
+                    preset.remove_stale_entries(|id| self.font_list_item_map.contains_key(id));
+                }
+                self.validate_presets();
+                self.schedule_save_prefs()
+            }
+            SettingsViewCommand::StaleEntryRemapClicked(warning) => {
+                let Some(to) = warning.suggestion else {
+                    return Task::none();
+                };
+                let Some(preset) = self.prefs.as_mut().and_then(|prefs| {
+                    prefs
+                        .presets
+                        .iter_mut()
+                        .find(|preset| preset.name == warning.preset_name)
+                }) else {
+                    return Task::none();
+                };
+                preset.remap_font_id(warning.entry.id(), &to);
+                let enabled = preset.is_font_enabled(&to);
+                self.validate_presets();
+
+                if self.prefs_selected_name.as_ref() != Some(&warning.preset_name) {
+                    return self.schedule_save_prefs();
+                }
+                if let Some(item) = self.font_list_item_map.get_mut(&to) {
+                    item.enabled = enabled;
+                }
+                Task::batch([
+                    send_xmessage(XMessage::FontEntries(self.create_font_entries())),
+                    self.schedule_save_prefs(),
+                ])
+            }
+            SettingsViewCommand::SubsetTextFileChanged(path) => {
+                self.subset_text_file = path;
+                Task::none()
             }
-            SettingsViewCommand::SettingsButtonClicked => send_xmessage(XMessage::SettingsClose),
             SettingsViewCommand::TextEditorAction(action) => {
                 let need_update = matches!(&action, text_editor::Action::Edit(_));
 
                 self.custom_text_content.perform(action);
 
                 if need_update {
-                    send_xmessage(XMessage::CustomText(self.custom_text_content.text()))
+                    self.update_custom_text()
                 } else {
                     Task::none()
                 }
             }
+            SettingsViewCommand::UndoClicked => {
+                let snapshot = self.prefs_history.undo();
+                self.restore_prefs(snapshot)
+            }
             // Propagate to App layer via Task so it can be converted to AppCommand::XMessage.
             SettingsViewCommand::SendXMessage(data) => send_xmessage(data),
             SettingsViewCommand::Sink => Task::none(),
@@ -334,27 +944,79 @@ impl SettingsView {
                     if self.save_prefs_version > 0 {
                         // Reset to 0 to indicate no pending unsaved changes.
                         self.save_prefs_version = 0;
+                        self.remember_custom_text();
                         if let Some(prefs) = &self.prefs {
                             let _ = self.prefs_repo.save(prefs.clone());
                         }
                     }
                     Task::none()
                 }
+                XMessage::FontCatalog(catalog) => {
+                    self.font_facts
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .retain(|font_name, _| !catalog.changed_font_names.contains(font_name));
+                    self.reconcile_font_list(catalog.entries);
+                    self.validate_presets();
+                    Task::batch([
+                        send_xmessage(XMessage::FontEntries(self.create_font_entries())),
+                        self.evaluate_rules(),
+                    ])
+                }
+                XMessage::FontsArranged(ids) => {
+                    let Some(preset) = self.selected_preset_mut() else {
+                        return Task::none();
+                    };
+                    preset.arrange_fonts(ids);
+                    let font_order = preset.font_order.clone();
+                    let sort_mode_changed = preset.sort_mode != SortMode::Custom;
+                    preset.sort_mode = SortMode::Custom;
+                    self.sort_mode = SortMode::Custom;
+                    Task::batch([
+                        send_xmessage(XMessage::FontOrder(font_order)),
+                        if sort_mode_changed {
+                            send_xmessage(XMessage::SortMode(SortMode::Custom))
+                        } else {
+                            Task::none()
+                        },
+                        self.schedule_save_prefs(),
+                    ])
+                }
                 // Load preferences asynchronously via Task::perform. The result
                 // is delivered as PrefsLoaded, which creates a default preset if
-                // needed and sends FontSize/FontEntries messages.
+                // needed and sends SpecimenTemplate/FontEntries messages.
                 XMessage::Init => {
                     let prefs_repo = self.prefs_repo.clone();
-                    Task::perform(
-                        async move { prefs_repo.retrieve() },
-                        |result| match result {
-                            Ok(prefs) => SettingsViewCommand::PrefsLoaded(prefs),
-                            Err(e) => {
-                                warn!(?e, "Failed to load preferences");
-                                SettingsViewCommand::PrefsLoaded(Preferences { presets: vec![] })
-                            }
-                        },
-                    )
+                    let sample_text_repo = self.sample_text_repo.clone();
+                    let font_list_repo = self.font_list_repo.clone();
+                    Task::batch([
+                        Task::perform(
+                            async move { font_list_repo.pack_signature() },
+                            SettingsViewCommand::PackScanned,
+                        ),
+                        Task::perform(
+                            async move { prefs_repo.retrieve() },
+                            |result| match result {
+                                Ok(prefs) => SettingsViewCommand::PrefsLoaded(prefs),
+                                Err(e) => {
+                                    warn!(?e, "Failed to load preferences");
+                                    SettingsViewCommand::PrefsLoaded(Preferences::default())
+                                }
+                            },
+                        ),
+                        Task::perform(
+                            async move { sample_text_repo.find_all() },
+                            SettingsViewCommand::SampleTextsLoaded,
+                        ),
+                    ])
+                }
+                XMessage::SettingsClose => {
+                    self.settings_open = false;
+                    Task::none()
+                }
+                XMessage::SettingsOpen => {
+                    self.settings_open = true;
+                    Task::none()
                 }
                 _ => Task::none(),
             },
@@ -362,48 +1024,145 @@ impl SettingsView {
     }
 
     pub fn subscription(&self) -> Subscription<SettingsViewCommand> {
-        Subscription::none()
+        let font_cache = if self.font_cache_panel_visible {
+            iced::time::every(std::time::Duration::from_secs(1))
+                .map(|_| SettingsViewCommand::FontCacheRefreshTick)
+        } else {
+            Subscription::none()
+        };
+        let pack = if self.pack_signature.is_some() {
+            iced::time::every(std::time::Duration::from_secs(PACK_POLL_INTERVAL_SECS))
+                .map(|_| SettingsViewCommand::PackPollTick)
+        } else {
+            Subscription::none()
+        };
+        // Text inputs capture the shortcuts for their own text, so only the ignored ones apply.
+        let undo = if self.settings_open {
+            iced::event::listen_with(|event, status, _id| match (event, status) {
+                (
+                    iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }),
+                    iced::event::Status::Ignored,
+                ) if modifiers.command() => match key.as_ref() {
+                    keyboard::Key::Character(c) if c.eq_ignore_ascii_case("z") => {
+                        Some(if modifiers.shift() {
+                            SettingsViewCommand::RedoClicked
+                        } else {
+                            SettingsViewCommand::UndoClicked
+                        })
+                    }
+                    keyboard::Key::Character(c) if c.eq_ignore_ascii_case("y") => {
+                        Some(SettingsViewCommand::RedoClicked)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+        // The button may be released outside of the presets, which cancels the drag.
+        let drag = if self.preset_drag.is_some() {
+            iced::event::listen_with(|event, _status, _id| match event {
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(SettingsViewCommand::PresetDragEnded)
+                }
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+        Subscription::batch([font_cache, pack, undo, drag])
     }
 
     pub fn view(&self) -> Element<'_, SettingsViewCommand> {
         let mut content = column![
-            container(settings_button_solid(
-                SettingsViewCommand::SettingsButtonClicked
-            ))
-            .padding(padding::right(WINDOW_BODY_MARGIN))
-            .align_right(Length::Fill)
+            container(
+                row![
+                    button(text("\u{21B6} Undo").size(12)).on_press_maybe(
+                        self.prefs_history
+                            .can_undo()
+                            .then_some(SettingsViewCommand::UndoClicked)
+                    ),
+                    button(text("\u{21B7} Redo").size(12)).on_press_maybe(
+                        self.prefs_history
+                            .can_redo()
+                            .then_some(SettingsViewCommand::RedoClicked)
+                    ),
+                    space().width(Length::Fill),
+                    settings_button_solid(SettingsViewCommand::SettingsButtonClicked),
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center)
+            )
+            .padding(padding::horizontal(WINDOW_BODY_MARGIN))
             .center_y(TOOLBAR_HEIGHT),
         ];
 
-        let mut content_inner = self
-            .view_presets()
+        let mut content_inner = self.view_presets();
+        if !self.stale_entries.is_empty() {
+            content_inner = content_inner
+                .push(divider())
+                .push(self.view_stale_entries());
+        }
+        content_inner = content_inner
+            .push(divider())
+            .push(self.view_preset_operations());
+        content_inner = content_inner
             .push(divider())
             .push(
-                container(
-                    text_editor(&self.custom_text_content)
-                        .placeholder("Custom text...")
-                        .on_action(SettingsViewCommand::TextEditorAction),
-                )
-                .height(73),
+                row![
+                    container(
+                        text_editor(&self.custom_text_content)
+                            .placeholder("Custom text...")
+                            .on_action(SettingsViewCommand::TextEditorAction),
+                    )
+                    .height(73)
+                    .width(Length::Fill),
+                    self.view_sample_text_history(),
+                ]
+                .spacing(4),
             )
+            .push(space().height(4))
+            .push(self.view_sample_texts())
+            .push(divider())
+            .push(self.view_template())
+            .push(divider())
+            .push(self.view_layout())
             .push(divider())
             .push(
                 row![
-                    "Font size:",
+                    "Sort:",
                     space().width(Length::Fill),
-                    number_input(
-                        &self.font_size,
-                        1..=1000,
-                        SettingsViewCommand::FontSizeUpdated
+                    pick_list(
+                        SortMode::ALL,
+                        Some(self.sort_mode),
+                        SettingsViewCommand::SortModeSelected
                     )
-                    .width(87),
+                    .text_size(12),
                 ]
                 .align_y(Alignment::Center),
             )
-            .push(divider());
+            .push(divider())
+            .push(self.view_export())
+            .push(divider())
+            .push(self.view_preset_file())
+            .push(divider())
+            .push(self.view_font_cache())
+            .push(divider())
+            .push(self.view_font_selection_mode());
 
+        // Fonts of rule-based presets are picked by the rules only.
+        let editable = self
+            .selected_preset()
+            .is_none_or(|preset| preset.font_selection_mode != FontSelectionMode::Rules);
+        let favourite_ids = self
+            .prefs
+            .as_ref()
+            .map(|prefs| prefs.favourite_ids.iter().collect::<HashSet<_>>())
+            .unwrap_or_default();
         for item in self.font_list_item_map.values() {
-            content_inner = content_inner.push(checkable_font_list_item(item));
+            let favourite = favourite_ids.contains(&item.font_entry.id);
+            content_inner = content_inner.push(checkable_font_list_item(item, favourite, editable));
         }
 
         content = content.push(scrollable(content_inner.padding(15).width(Length::Fill)));
@@ -440,12 +1199,22 @@ impl SettingsView {
                 ]
             } else {
                 let name = preset.name.clone();
-                row![radio(
-                    &preset.name,
-                    &preset.name,
-                    self.prefs_selected_name.as_ref(),
-                    move |_| SettingsViewCommand::PresetSelected(name.clone()),
-                )]
+                let mut grip =
+                    mouse_area(container(text("\u{2261}")).padding(padding::horizontal(4)));
+                if !is_editing {
+                    grip = grip
+                        .on_press(SettingsViewCommand::PresetDragStarted(name.clone()))
+                        .interaction(mouse::Interaction::Grab);
+                }
+                row![
+                    grip,
+                    radio(
+                        &preset.name,
+                        &preset.name,
+                        self.prefs_selected_name.as_ref(),
+                        move |_| SettingsViewCommand::PresetSelected(name.clone()),
+                    )
+                ]
             }
             .align_y(Alignment::Center);
 
@@ -475,7 +1244,19 @@ impl SettingsView {
             }
             preset_row = preset_row.push(delete_btn);
 
-            content = content.push(preset_row);
+            content = content.push(match &self.preset_drag {
+                Some(drag) => {
+                    let area = mouse_area(preset_row)
+                        .on_enter(SettingsViewCommand::PresetDragHovered(preset.name.clone()))
+                        .interaction(mouse::Interaction::Grabbing);
+                    if drag.target.as_ref() == Some(&preset.name) && drag.name != preset.name {
+                        container(area).style(drop_target_style).into()
+                    } else {
+                        Element::from(area)
+                    }
+                }
+                None => preset_row.into(),
+            });
         }
 
         let selected_index = self
@@ -506,14 +1287,730 @@ impl SettingsView {
             toolbar_row = toolbar_row.push(down_btn);
         }
 
-        content.push(toolbar_row)
-    }
+        content = content.push(toolbar_row);
 
-    // Debounced save: increment save_prefs_version and wait
-    // SAVE_PREFS_DEBOUNCE_MILLIS before firing SavePrefsRequested. Only the
-    // request whose version matches the current save_prefs_version will
-    // actually persist, so rapid changes result in a single save.
+        if !self.builtin_presets.is_empty() {
+            content = content.push(space().height(4)).push("Built-in:");
+        }
+        for preset in &self.builtin_presets {
+            let mut copy_btn = preset_action_btn(copy_icon());
+            if !is_editing {
+                copy_btn = copy_btn.on_press(SettingsViewCommand::BuiltinPresetCopyClicked(
+                    preset.name.clone(),
+                ));
+            }
+            content = content.push(
+                row![
+                    text(&preset.name),
+                    space().width(Length::Fill),
+                    text(format!("{} fonts", preset.enabled_ids.len())).size(12),
+                    copy_btn,
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        content
+    }
+
+    fn view_preset_operations(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let names = self
+            .prefs
+            .iter()
+            .flat_map(|prefs| &prefs.presets)
+            .filter(|preset| self.has_known_fonts(preset))
+            .map(|preset| preset.name.clone())
+            .collect::<Vec<_>>();
+        let has_hidden_presets = self
+            .prefs
+            .iter()
+            .flat_map(|prefs| &prefs.presets)
+            .any(|preset| !self.has_known_fonts(preset));
+        let operands = self.preset_operands();
+
+        let mut operation_row = row![].spacing(4);
+        for operation in PresetSetOperation::ALL {
+            operation_row = operation_row.push(
+                button(text(format!("A {} B", operation.symbol()))).on_press_maybe(
+                    operands.map(|_| SettingsViewCommand::PresetSetOperationClicked(operation)),
+                ),
+            );
+        }
+        operation_row = operation_row.push(
+            button(if self.preset_diff_visible {
+                "Hide diff"
+            } else {
+                "Diff"
+            })
+            .on_press(SettingsViewCommand::PresetDiffToggled),
+        );
+
+        let mut content = column![
+            "Combine presets:",
+            row![
+                pick_list(
+                    names.clone(),
+                    self.preset_operands.0.clone(),
+                    SettingsViewCommand::PresetOperandASelected
+                )
+                .placeholder("Preset A")
+                .text_size(12),
+                pick_list(
+                    names,
+                    self.preset_operands.1.clone(),
+                    SettingsViewCommand::PresetOperandBSelected
+                )
+                .placeholder("Preset B")
+                .text_size(12),
+            ]
+            .spacing(4),
+        ]
+        .push(has_hidden_presets.then(|| {
+            text("Rule-based presets are listed only while selected, once their rules have run.")
+                .size(12)
+        }))
+        .push(operation_row)
+        .spacing(4);
+
+        let Some((a, b)) = operands.filter(|_| self.preset_diff_visible) else {
+            return content;
+        };
+        let diff = PresetDiff::new(
+            self.font_list_item_map.keys().map(String::as_str),
+            &self.preset_font_ids(a),
+            &self.preset_font_ids(b),
+        );
+        for (label, ids) in [
+            (format!("Only in {}", a.name), diff.only_a),
+            (format!("Only in {}", b.name), diff.only_b),
+            ("In both".to_string(), diff.both),
+        ] {
+            content = content.push(text(format!("{label} ({})", ids.len())));
+            for id in ids {
+                let font_name = self
+                    .font_list_item_map
+                    .get(&id)
+                    .map(|item| {
+                        let entry = &item.font_entry;
+                        entry
+                            .display_name
+                            .clone()
+                            .unwrap_or(entry.font_name.to_string())
+                    })
+                    .unwrap_or(id);
+                content = content.push(text(font_name).size(12));
+            }
+        }
+
+        content
+    }
+
+    fn view_stale_entries(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let mut content = column![
+            text(format!(
+                "{} preset entries do not match the font list",
+                self.stale_entries.len()
+            ))
+            .style(text::warning),
+        ]
+        .spacing(4);
+
+        for warning in &self.stale_entries {
+            let label = match &warning.entry {
+                StaleEntry::Duplicate(id) => {
+                    format!("{}: {id} is listed twice", warning.preset_name)
+                }
+                StaleEntry::Orphaned(id) => format!("{}: {id} is missing", warning.preset_name),
+            };
+            let mut entry_row = row![text(label).size(12).width(Length::Fill)]
+                .spacing(4)
+                .align_y(Alignment::Center);
+            if let Some(suggestion) = &warning.suggestion {
+                entry_row =
+                    entry_row
+                        .push(button(text(format!("Use {suggestion}")).size(12)).on_press(
+                            SettingsViewCommand::StaleEntryRemapClicked(warning.clone()),
+                        ));
+            }
+            content = content.push(entry_row);
+        }
+
+        content.push(button("Clean up").on_press(SettingsViewCommand::StaleEntriesCleanupClicked))
+    }
+
+    fn view_sample_text_history(&self) -> Element<'_, SettingsViewCommand> {
+        let history = self
+            .prefs
+            .as_ref()
+            .map(|prefs| {
+                prefs
+                    .sample_text_history
+                    .iter()
+                    .cloned()
+                    .map(SampleTextHistoryItem)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        pick_list(
+            history,
+            None::<SampleTextHistoryItem>,
+            SettingsViewCommand::SampleTextHistorySelected,
+        )
+        .placeholder("History")
+        .text_size(12)
+        .width(80)
+        .into()
+    }
+
+    fn view_sample_texts(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let name = self.sample_text_name.trim();
+        let can_save = !name.is_empty() && !self.custom_text_content.text().trim().is_empty();
+        let can_remove = self
+            .sample_texts
+            .iter()
+            .any(|entry| entry.user_defined && entry.name == name);
+
+        column![
+            pick_list(
+                self.sample_texts.as_slice(),
+                None::<SampleText>,
+                SettingsViewCommand::SampleTextSelected,
+            )
+            .placeholder("Sample texts")
+            .text_size(12)
+            .width(Length::Fill),
+            row![
+                text_input("Name", &self.sample_text_name)
+                    .size(12)
+                    .on_input(SettingsViewCommand::SampleTextNameChanged)
+                    .on_submit_maybe(
+                        can_save.then_some(SettingsViewCommand::SampleTextSaveClicked)
+                    ),
+                button(text("Save").size(12))
+                    .on_press_maybe(can_save.then_some(SettingsViewCommand::SampleTextSaveClicked)),
+                button(text("Remove").size(12)).on_press_maybe(
+                    can_remove.then_some(SettingsViewCommand::SampleTextRemoveClicked)
+                ),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(4)
+    }
+
+    fn view_template(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let mut content = column!["Specimen:"].spacing(4);
+
+        for row in &self.template.rows {
+            let kind = row.kind;
+            content = content.push(
+                row![
+                    checkbox(row.enabled)
+                        .label(kind.label())
+                        .on_toggle(move |enabled| {
+                            SettingsViewCommand::SpecimenRowToggled(kind, enabled)
+                        }),
+                    space().width(Length::Fill),
+                    number_input(&row.font_size, 1..=1000, move |size| {
+                        SettingsViewCommand::SpecimenRowSizeUpdated(kind, size)
+                    })
+                    .width(87),
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+
+        content.push(
+            checkbox(self.template.fit_to_width)
+                .label("Fit headline to width")
+                .on_toggle(SettingsViewCommand::FitToWidthToggled),
+        )
+    }
+
+    fn view_layout(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let kind = Some(self.layout.kind);
+        column![
+            row![
+                "Layout:",
+                space().width(Length::Fill),
+                radio(
+                    "List",
+                    PreviewLayoutKind::List,
+                    kind,
+                    SettingsViewCommand::LayoutKindSelected
+                ),
+                radio(
+                    "Grid",
+                    PreviewLayoutKind::Grid,
+                    kind,
+                    SettingsViewCommand::LayoutKindSelected
+                ),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+            row![
+                "Grid columns:",
+                space().width(Length::Fill),
+                number_input(
+                    &self.layout.grid_columns,
+                    1..=12,
+                    SettingsViewCommand::GridColumnsUpdated
+                )
+                .width(87),
+            ]
+            .align_y(Alignment::Center),
+        ]
+        .spacing(4)
+    }
+
+    fn view_export(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let running = matches!(self.export_status, Some(ExportStatus::Running));
+
+        let enabled = !running && self.prefs_selected_name.is_some();
+        let export_btn = |label, command| {
+            let mut btn = button(label);
+            if enabled {
+                btn = btn.on_press(command);
+            }
+            btn
+        };
+
+        let mut content = column![
+            "Export:",
+            row![
+                export_btn("PDF", SettingsViewCommand::ExportPdfClicked),
+                export_btn("HTML", SettingsViewCommand::ExportHtmlClicked),
+                export_btn("Subset", SettingsViewCommand::ExportSubsetClicked),
+            ]
+            .spacing(4),
+            text_input("Subset text file (optional)", &self.subset_text_file)
+                .on_input(SettingsViewCommand::SubsetTextFileChanged),
+        ]
+        .spacing(4);
+
+        if let Some(status) = &self.export_status {
+            content = content.push(text(status.to_string()).size(12));
+        }
+
+        content
+    }
+
+    fn view_preset_file(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let selected = self.prefs_selected_name.is_some();
+        let mut content = column![
+            "Share presets:",
+            row![
+                pick_list(
+                    PresetFileFormat::ALL,
+                    Some(self.preset_file_format),
+                    SettingsViewCommand::PresetFileFormatSelected
+                )
+                .text_size(12),
+                button("Export")
+                    .on_press_maybe(selected.then_some(SettingsViewCommand::PresetExportClicked)),
+                button("Export all").on_press_maybe(
+                    selected.then_some(SettingsViewCommand::PresetExportAllClicked)
+                ),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center),
+            row![
+                text_input("Preset file to import", &self.preset_file)
+                    .on_input(SettingsViewCommand::PresetFileChanged)
+                    .on_submit(SettingsViewCommand::PresetImportClicked),
+                button("Import").on_press(SettingsViewCommand::PresetImportClicked),
+            ]
+            .spacing(4),
+            row![
+                button("Copy code")
+                    .on_press_maybe(selected.then_some(SettingsViewCommand::PresetCodeCopyClicked)),
+                button("Paste code").on_press(SettingsViewCommand::PresetCodePasteClicked),
+            ]
+            .spacing(4),
+        ]
+        .spacing(4);
+
+        if let Some(status) = &self.preset_file_status {
+            content = content.push(text(status).size(12));
+        }
+
+        content
+    }
+
+    fn view_font_selection_mode(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let preset = self.selected_preset();
+        let selected = preset.map(|preset| preset.font_selection_mode);
+        let mut content = column![
+            "Fonts:",
+            radio(
+                "Picked, hide new fonts",
+                FontSelectionMode::AllowList,
+                selected,
+                SettingsViewCommand::FontSelectionModeSelected
+            ),
+            radio(
+                "Picked, show new fonts",
+                FontSelectionMode::DenyList,
+                selected,
+                SettingsViewCommand::FontSelectionModeSelected
+            ),
+            radio(
+                "Matching rules",
+                FontSelectionMode::Rules,
+                selected,
+                SettingsViewCommand::FontSelectionModeSelected
+            ),
+        ]
+        .spacing(4);
+
+        if !self.new_font_ids.is_empty() {
+            content = content.push(
+                text(format!(
+                    "{} new fonts since the last run",
+                    self.new_font_ids.len()
+                ))
+                .size(12),
+            );
+        }
+
+        if let Some(preset) = preset
+            && preset.font_selection_mode == FontSelectionMode::Rules
+        {
+            content = content.push(self.view_rules(&preset.rules));
+        }
+
+        content
+    }
+
+    fn view_rules<'a>(
+        &self,
+        rules: &'a [FontRule],
+    ) -> iced::widget::Column<'a, SettingsViewCommand> {
+        let mut content = column![].spacing(4);
+
+        for (index, rule) in rules.iter().enumerate() {
+            let changed = move |rule| SettingsViewCommand::RuleChanged(index, rule);
+            let editor: Element<'_, SettingsViewCommand> = match rule {
+                FontRule::Collection(value) => text_input("Folder, e.g. 桜", value)
+                    .on_input(move |value| changed(FontRule::Collection(value)))
+                    .into(),
+                FontRule::Family(value) => text_input("Part of the name", value)
+                    .on_input(move |value| changed(FontRule::Family(value)))
+                    .into(),
+                FontRule::WeightRange(min, max) => {
+                    let (min, max) = (*min, *max);
+                    row![
+                        number_input(&min, 1..=1000, move |min| {
+                            changed(FontRule::WeightRange(min, max))
+                        })
+                        .step(100)
+                        .width(87),
+                        "to",
+                        number_input(&max, 1..=1000, move |max| {
+                            changed(FontRule::WeightRange(min, max))
+                        })
+                        .step(100)
+                        .width(87),
+                    ]
+                    .spacing(4)
+                    .align_y(Alignment::Center)
+                    .into()
+                }
+                // The rule matches nothing while the pattern is invalid, so the reason is shown.
+                FontRule::NameRegex(value) => column![
+                    text_input("Regular expression", value)
+                        .on_input(move |value| changed(FontRule::NameRegex(value))),
+                ]
+                .push(Regex::new(value).err().map(|e| {
+                    text(e.to_string())
+                        .size(11)
+                        .font(Font::MONOSPACE)
+                        .style(text::danger)
+                }))
+                .spacing(2)
+                .into(),
+                FontRule::GlyphCoverage(percent) => row![
+                    number_input(percent, 1..=100, move |percent| {
+                        changed(FontRule::GlyphCoverage(percent))
+                    })
+                    .width(87),
+                    "% of the custom text",
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center)
+                .into(),
+                FontRule::SupportsCustomText => {
+                    text("Every character of the custom text").size(12).into()
+                }
+                FontRule::Favourite => text("Fonts starred in the font list").size(12).into(),
+            };
+
+            content = content.push(
+                row![
+                    column![text(rule.kind().to_string()).size(12), editor]
+                        .spacing(2)
+                        .width(Length::Fill),
+                    preset_action_btn(trash_icon())
+                        .on_press(SettingsViewCommand::RuleRemoved(index)),
+                ]
+                .spacing(4)
+                .align_y(Alignment::End),
+            );
+        }
+
+        content.push(
+            row![
+                "Add rule:",
+                space().width(Length::Fill),
+                pick_list(
+                    FontRuleKind::ALL,
+                    None::<FontRuleKind>,
+                    SettingsViewCommand::RuleAdded
+                )
+                .placeholder("Choose...")
+                .text_size(12),
+            ]
+            .align_y(Alignment::Center),
+        )
+    }
+
+    /// Debug panel listing the font files cached by [`FontListRepository::load_font`] and the
+    /// fonts registered with iced.
+    fn view_font_cache(&self) -> iced::widget::Column<'_, SettingsViewCommand> {
+        let mut content = column![
+            checkbox(self.font_cache_panel_visible)
+                .label("Show font cache")
+                .on_toggle(SettingsViewCommand::FontCachePanelToggled),
+        ]
+        .spacing(4);
+        if !self.font_cache_panel_visible {
+            return content;
+        }
+
+        let budget_mb = self
+            .prefs
+            .as_ref()
+            .map(|prefs| prefs.font_cache_budget_mb)
+            .unwrap_or(DEFAULT_FONT_CACHE_BUDGET_MB);
+        let status = &self.font_cache_status;
+        content = content
+            .push(
+                row![
+                    "File cache (MiB):",
+                    space().width(Length::Fill),
+                    number_input(
+                        &budget_mb,
+                        16..=4096,
+                        SettingsViewCommand::FontCacheBudgetUpdated
+                    )
+                    .width(87),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .push(
+                text(format!(
+                    "{} files cached, {:.1} / {:.1} MiB",
+                    status.fonts.len(),
+                    bytes_to_mib(status.used_bytes),
+                    bytes_to_mib(status.budget_bytes),
+                ))
+                .size(12),
+            );
+        // Most recently used first.
+        for (font_name, bytes) in status.fonts.iter().rev() {
+            content = content.push(font_size_row(font_name, *bytes));
+        }
+
+        let registered_bytes = status
+            .registered_fonts
+            .iter()
+            .map(|(_, bytes)| bytes)
+            .sum::<usize>();
+        content = content.push(
+            text(format!(
+                "{} fonts registered, {:.1} MiB kept until the app quits",
+                status.registered_fonts.len(),
+                bytes_to_mib(registered_bytes),
+            ))
+            .size(12),
+        );
+        for (font_name, bytes) in &status.registered_fonts {
+            content = content.push(font_size_row(font_name, *bytes));
+        }
+
+        content
+    }
+
+    /// Runs `export` in the background with the fonts of the selected preset. Font files are
+    /// resolved in the background as well since they are read from the pack or, for the first
+    /// lookup, after scanning the system fonts.
+    fn start_export<F>(&mut self, export: F) -> Task<SettingsViewCommand>
+    where
+        F: FnOnce(&Path, &PresetExport, &FontFileRepository) -> Fallible<ExportResult>
+            + Send
+            + 'static,
+    {
+        if matches!(self.export_status, Some(ExportStatus::Running)) {
+            return Task::none();
+        }
+        let Some(name) = self.prefs_selected_name.clone() else {
+            return Task::none();
+        };
+        self.export_status = Some(ExportStatus::Running);
+
+        let font_entries = self.create_font_entries();
+        let font_size = self.template.headline_size();
+        let custom_text = self.custom_text();
+        let font_list_repo = self.font_list_repo.clone();
+        let font_file_repo = self.font_file_repo.clone();
+        let export_dir = self.export_dir.clone();
+        Task::perform(
+            async move {
+                let fonts = specimen_fonts(font_entries, &font_list_repo, &font_file_repo);
+                let preset = PresetExport {
+                    name,
+                    font_size,
+                    fonts,
+                    custom_text,
+                };
+                export(&export_dir, &preset, &font_file_repo)
+            },
+            |result| {
+                if let Err(e) = &result {
+                    warn!(?e, "failed to export");
+                }
+                SettingsViewCommand::ExportFinished(result.map_err(|e| format!("{e:#}")))
+            },
+        )
+    }
+
+    /// Inserts an editable copy of `source` at `index` and selects it.
+    fn copy_preset(&mut self, source: Preset, index: usize) -> Task<SettingsViewCommand> {
+        let Some(prefs) = self.prefs.as_mut() else {
+            return Task::none();
+        };
+        let new_name = next_copy_name(&source.name, &prefs.presets);
+        let new_preset = Preset {
+            name: new_name.clone(),
+            ..source
+        };
+        prefs.presets.insert(index, new_preset.clone());
+        self.prefs_selected_name = Some(new_name);
+        self.apply_preset(&new_preset);
+        self.validate_presets();
+
+        Task::batch([self.notify_preset_applied(), self.schedule_save_prefs()])
+    }
+
+    /// Appends shared presets, renaming the ones whose name is already taken.
+    fn add_presets(&mut self, presets: Vec<Preset>) {
+        let Some(prefs) = self.prefs.as_mut() else {
+            return;
+        };
+
+        for mut preset in presets {
+            preset.name = preset.name.trim().to_string();
+            if preset.name.is_empty() {
+                preset.name = next_preset_name(&prefs.presets);
+            } else if prefs.presets.iter().any(|p| p.name == preset.name) {
+                preset.name = next_copy_name(&preset.name, &prefs.presets);
+            }
+            prefs.presets.push(preset);
+        }
+        self.validate_presets();
+    }
+
+    /// Writes the selected preset, or all presets, to a new file in the export folder.
+    fn export_presets(&self, all: bool) -> Task<SettingsViewCommand> {
+        let Some(prefs) = &self.prefs else {
+            return Task::none();
+        };
+        let (file_stem, presets) = if all {
+            ("presets".to_string(), prefs.presets.clone())
+        } else {
+            let Some(preset) = self
+                .prefs_selected_name
+                .as_ref()
+                .and_then(|name| prefs.presets.iter().find(|preset| &preset.name == name))
+            else {
+                return Task::none();
+            };
+            (preset.name.clone(), vec![preset.clone()])
+        };
+
+        let prefs_repo = self.prefs_repo.clone();
+        let export_dir = self.export_dir.clone();
+        let extension = self.preset_file_format.extension();
+        Task::perform(
+            async move {
+                let path = unique_path(&export_dir, &file_stem, extension);
+                prefs_repo.write_preset_file(presets, &path).map(|_| path)
+            },
+            |result| {
+                if let Err(e) = &result {
+                    warn!(?e, "failed to export presets");
+                }
+                SettingsViewCommand::PresetExportFinished(result.map_err(|e| format!("{e:#}")))
+            },
+        )
+    }
+
+    /// Records the change of `prefs` in the undo history and saves them after a while.
     fn schedule_save_prefs(&mut self) -> Task<SettingsViewCommand> {
+        self.record_prefs_history(None);
+        self.debounce_save_prefs()
+    }
+
+    fn record_prefs_history(&mut self, group: Option<&'static str>) {
+        if let Some(snapshot) = self.prefs_snapshot() {
+            self.prefs_history.record(snapshot, group);
+        }
+    }
+
+    fn prefs_snapshot(&self) -> Option<PrefsSnapshot> {
+        Some(PrefsSnapshot {
+            prefs: self.prefs.clone()?,
+            selected_name: self.prefs_selected_name.clone(),
+        })
+    }
+
+    /// Puts back preferences from the undo history. They are saved like any other change.
+    fn restore_prefs(&mut self, snapshot: Option<PrefsSnapshot>) -> Task<SettingsViewCommand> {
+        let (Some(snapshot), Some(current)) = (snapshot, self.prefs.as_ref()) else {
+            return Task::none();
+        };
+        let mut prefs = snapshot.prefs;
+        prefs.sample_text_history = current.sample_text_history.clone();
+        prefs.favourite_ids = current.favourite_ids.clone();
+        prefs.catalog_version = current.catalog_version;
+
+        self.font_list_repo
+            .set_font_cache_budget(mib_to_bytes(prefs.font_cache_budget_mb));
+        self.font_cache_status = self.font_list_repo.font_cache_status();
+
+        let preset = snapshot
+            .selected_name
+            .and_then(|name| prefs.presets.iter().find(|preset| preset.name == name))
+            .or(prefs.presets.first())
+            .cloned();
+        self.editing_preset = None;
+        self.prefs_selected_name = preset.as_ref().map(|preset| preset.name.clone());
+        self.prefs = Some(prefs);
+        if let Some(preset) = preset {
+            self.apply_preset(&preset);
+        }
+        self.validate_presets();
+
+        Task::batch([self.notify_preset_applied(), self.debounce_save_prefs()])
+    }
+
+    // Debounced save: increment save_prefs_version and wait
+    // SAVE_PREFS_DEBOUNCE_MILLIS before firing SavePrefsRequested. Only the
+    // request whose version matches the current save_prefs_version will
+    // actually persist, so rapid changes result in a single save.
+    fn debounce_save_prefs(&mut self) -> Task<SettingsViewCommand> {
         self.save_prefs_version += 1;
         let version = self.save_prefs_version;
         Task::perform(
@@ -525,10 +2022,35 @@ impl SettingsView {
         )
     }
 
+    fn save_prefs(&self) -> Task<SettingsViewCommand> {
+        let Some(prefs) = self.prefs.clone() else {
+            return Task::none();
+        };
+        let prefs_repo = self.prefs_repo.clone();
+        Task::perform(
+            async move {
+                match prefs_repo.save(prefs) {
+                    Ok(_) => info!("saved preferences"),
+                    Err(e) => warn!(?e, "failed to save preferences"),
+                };
+            },
+            |_| SettingsViewCommand::Sink,
+        )
+    }
+
     fn notify_preset_applied(&self) -> Task<SettingsViewCommand> {
         Task::batch([
-            send_xmessage(XMessage::FontSize(self.font_size)),
+            send_xmessage(XMessage::CustomText(self.custom_text_content.text())),
+            send_xmessage(XMessage::SpecimenTemplate(self.template.clone())),
+            send_xmessage(XMessage::FontOrder(
+                self.selected_preset()
+                    .map(|preset| preset.font_order.clone())
+                    .unwrap_or_default(),
+            )),
+            send_xmessage(XMessage::SortMode(self.sort_mode)),
+            send_xmessage(XMessage::PreviewLayout(self.layout)),
             send_xmessage(XMessage::FontEntries(self.create_font_entries())),
+            self.evaluate_rules(),
         ])
     }
 
@@ -540,25 +2062,239 @@ impl SettingsView {
             return Task::none();
         };
         let new_index = index as i32 + direction;
-        if new_index < 0 || new_index >= prefs.presets.len() as i32 {
+        if new_index < 0 {
             return Task::none();
         }
-        prefs.presets.swap(index, new_index as usize);
+        self.move_preset_to(name, new_index as usize)
+    }
+
+    /// Moves the preset to `new_index`, shifting the presets in between.
+    fn move_preset_to(&mut self, name: &str, new_index: usize) -> Task<SettingsViewCommand> {
+        let Some(prefs) = self.prefs.as_mut() else {
+            return Task::none();
+        };
+        let Some(index) = prefs.presets.iter().position(|p| p.name == name) else {
+            return Task::none();
+        };
+        if index == new_index || new_index >= prefs.presets.len() {
+            return Task::none();
+        }
+        let preset = prefs.presets.remove(index);
+        prefs.presets.insert(new_index, preset);
         self.schedule_save_prefs()
     }
 
     fn apply_preset(&mut self, preset: &Preset) {
-        self.font_size = preset.font_size;
-        let enable_paths = preset
-            .enable_paths
-            .iter()
-            .map(String::as_str)
-            .collect::<HashSet<&str>>();
+        self.custom_text_content = text_editor::Content::with_text(&preset.custom_text);
+        self.template = preset.template.clone();
+        self.sort_mode = preset.sort_mode;
+        self.layout = preset.layout;
         for item in self.font_list_item_map.values_mut() {
-            item.enabled = enable_paths.contains(item.font_entry.filepath.as_str());
+            item.enabled = preset.is_font_enabled(&item.font_entry.id);
+        }
+    }
+
+    fn update_template(&mut self, f: impl Fn(&mut SpecimenTemplate)) -> Task<SettingsViewCommand> {
+        f(&mut self.template);
+
+        if let Some(preset) = self.selected_preset_mut() {
+            f(&mut preset.template);
+        }
+
+        Task::batch([
+            send_xmessage(XMessage::SpecimenTemplate(self.template.clone())),
+            self.schedule_save_prefs(),
+        ])
+    }
+
+    fn update_layout(&mut self, f: impl Fn(&mut PreviewLayout)) -> Task<SettingsViewCommand> {
+        f(&mut self.layout);
+
+        if let Some(preset) = self.selected_preset_mut() {
+            f(&mut preset.layout);
+        }
+
+        Task::batch([
+            send_xmessage(XMessage::PreviewLayout(self.layout)),
+            self.schedule_save_prefs(),
+        ])
+    }
+
+    /// Stores the edited custom text into the selected preset and notifies MainView.
+    fn update_custom_text(&mut self) -> Task<SettingsViewCommand> {
+        let custom_text = self.custom_text_content.text();
+
+        if let Some(preset) = self.selected_preset_mut() {
+            preset.custom_text = custom_text.clone();
+        }
+
+        let rules_need_text = self.selected_preset().is_some_and(|preset| {
+            preset.font_selection_mode == FontSelectionMode::Rules
+                && preset.rules.iter().any(FontRule::needs_custom_text)
+        });
+        // Undone as a whole rather than one keystroke at a time.
+        self.record_prefs_history(Some("custom-text"));
+        Task::batch([
+            send_xmessage(XMessage::CustomText(custom_text)),
+            if rules_need_text {
+                self.evaluate_rules()
+            } else {
+                Task::none()
+            },
+            self.debounce_save_prefs(),
+        ])
+    }
+
+    fn update_rules(&mut self, f: impl FnOnce(&mut Vec<FontRule>)) -> Task<SettingsViewCommand> {
+        let Some(preset) = self.selected_preset_mut() else {
+            return Task::none();
+        };
+        f(&mut preset.rules);
+        Task::batch([self.evaluate_rules(), self.schedule_save_prefs()])
+    }
+
+    /// Finds the fonts of the selected preset in [`FontSelectionMode::Rules`] in the background.
+    /// Font files are read only for rules that look into them, and their facts are cached.
+    fn evaluate_rules(&self) -> Task<SettingsViewCommand> {
+        let Some(preset) = self
+            .selected_preset()
+            .filter(|preset| preset.font_selection_mode == FontSelectionMode::Rules)
+        else {
+            return Task::none();
+        };
+
+        let rules = preset.rules.clone();
+        let favourite_ids = self
+            .prefs
+            .as_ref()
+            .map(|prefs| prefs.favourite_ids.iter().cloned().collect::<HashSet<_>>())
+            .unwrap_or_default();
+        let font_entries = self
+            .font_list_item_map
+            .values()
+            .map(|item| item.font_entry.clone())
+            .collect::<Vec<_>>();
+        let custom_text = self.custom_text_content.text();
+        let font_facts = self.font_facts.clone();
+        let font_list_repo = self.font_list_repo.clone();
+        let font_file_repo = self.font_file_repo.clone();
+        Task::perform(
+            async move {
+                let ids = find_matching_fonts(
+                    &rules,
+                    &font_entries,
+                    &favourite_ids,
+                    &custom_text,
+                    |entry| {
+                        let lock = || {
+                            font_facts
+                                .lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        };
+                        if let Some(facts) = lock().get(entry.font_name) {
+                            return facts.clone();
+                        }
+
+                        let facts = font_list_repo
+                            .read_font_file(entry)
+                            .or_else(|| font_file_repo.find_by_name(entry.font_name))
+                            .and_then(|file| match read_font_facts(&file) {
+                                Ok(data) => Some(Arc::new(data)),
+                                Err(e) => {
+                                    warn!(?e, font_name = entry.font_name, "failed to read font");
+                                    None
+                                }
+                            });
+                        lock().insert(entry.font_name, facts.clone());
+                        facts
+                    },
+                );
+                (rules, ids)
+            },
+            |(rules, ids)| SettingsViewCommand::RulesEvaluated(rules, ids),
+        )
+    }
+
+    fn save_user_defined_sample_texts(&self) -> Task<SettingsViewCommand> {
+        let entries = self
+            .sample_texts
+            .iter()
+            .filter(|entry| entry.user_defined)
+            .cloned()
+            .collect::<Vec<_>>();
+        let sample_text_repo = self.sample_text_repo.clone();
+        Task::perform(
+            async move {
+                match sample_text_repo.save_user_defined(entries) {
+                    Ok(_) => info!("saved sample texts"),
+                    Err(e) => warn!(?e, "failed to save sample texts"),
+                };
+            },
+            |_| SettingsViewCommand::Sink,
+        )
+    }
+
+    /// Records the current custom text in the history. Called when the text has settled (on save
+    /// or when leaving the preset) rather than on every keystroke, so that the history does not
+    /// fill up with partially typed texts.
+    fn remember_custom_text(&mut self) {
+        let custom_text = self.custom_text_content.text();
+        if let Some(prefs) = self.prefs.as_mut() {
+            prefs.push_sample_text_history(&custom_text);
         }
     }
 
+    /// Presets chosen to be combined, unless a rule-based one whose fonts are not known.
+    fn preset_operands(&self) -> Option<(&Preset, &Preset)> {
+        let prefs = self.prefs.as_ref()?;
+        let find = |name: &Option<String>| {
+            let name = name.as_ref()?;
+            prefs
+                .presets
+                .iter()
+                .find(|preset| &preset.name == name && self.has_known_fonts(preset))
+        };
+        Some((
+            find(&self.preset_operands.0)?,
+            find(&self.preset_operands.1)?,
+        ))
+    }
+
+    /// Whether the fonts of `preset` are known without evaluating rules. Those of a rule-based
+    /// preset are only known while it is selected, from the results shown in the font list.
+    fn has_known_fonts(&self, preset: &Preset) -> bool {
+        preset.font_selection_mode != FontSelectionMode::Rules
+            || self.prefs_selected_name.as_ref() == Some(&preset.name)
+    }
+
+    /// IDs of the catalog fonts enabled by `preset`, in catalog order. Those of the selected
+    /// rule-based preset are the results of its rules.
+    fn preset_font_ids(&self, preset: &Preset) -> Vec<String> {
+        let is_evaluated = preset.font_selection_mode == FontSelectionMode::Rules
+            && self.prefs_selected_name.as_ref() == Some(&preset.name);
+        self.font_list_item_map
+            .iter()
+            .filter(|(id, item)| {
+                if is_evaluated {
+                    item.enabled
+                } else {
+                    preset.is_font_enabled(id)
+                }
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    fn selected_preset(&self) -> Option<&Preset> {
+        let selected_name = self.prefs_selected_name.as_ref()?;
+        self.prefs.as_ref().and_then(|prefs| {
+            prefs
+                .presets
+                .iter()
+                .find(|preset| &preset.name == selected_name)
+        })
+    }
+
     fn selected_preset_mut(&mut self) -> Option<&mut Preset> {
         let selected_name = self.prefs_selected_name.as_ref()?;
         self.prefs.as_mut().and_then(|prefs| {
@@ -569,6 +2305,90 @@ impl SettingsView {
         })
     }
 
+    /// Custom text for exports. Falls back to the same default sample as MainView.
+    fn custom_text(&self) -> String {
+        let text = self.custom_text_content.text();
+        let text = text.trim_end();
+        if text.is_empty() {
+            DEFAULT_SAMPLE_TEXT.to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Rebuilds [`Self::font_list_item_map`] in the order of a reloaded catalog. Entries already
+    /// listed keep their state, and new entries follow the selected preset. Presets are left as
+    /// they are, so removed fonts come back once they are restored.
+    fn reconcile_font_list(&mut self, font_entries: Vec<FontEntry>) {
+        let selected_preset = self.prefs_selected_name.as_ref().and_then(|name| {
+            self.prefs
+                .as_ref()
+                .and_then(|prefs| prefs.presets.iter().find(|preset| &preset.name == name))
+        });
+
+        let mut font_list_item_map = IndexMap::with_capacity(font_entries.len());
+        for font_entry in font_entries {
+            let enabled = match (self.font_list_item_map.get(&font_entry.id), selected_preset) {
+                (Some(item), _) => item.enabled,
+                (None, Some(preset)) => preset.is_font_enabled(&font_entry.id),
+                (None, None) => true,
+            };
+            let is_new = self.new_font_ids.contains(&font_entry.id);
+            font_list_item_map.insert(
+                font_entry.id.to_owned(),
+                FontListItem {
+                    enabled,
+                    font_entry,
+                    is_new,
+                },
+            );
+        }
+        self.font_list_item_map = font_list_item_map;
+        self.stale_entries_key = None;
+        self.similar_font_ids.clear();
+    }
+
+    /// Refreshes [`Self::stale_entries`] if the ID lists of the presets or the catalog changed
+    /// since the last call. Stale entries are only reported, since a font may come back with the
+    /// next pack release.
+    fn validate_presets(&mut self) {
+        let Some(prefs) = &self.prefs else {
+            self.stale_entries.clear();
+            self.stale_entries_key = None;
+            return;
+        };
+
+        let mut hasher = DefaultHasher::new();
+        for preset in &prefs.presets {
+            (&preset.name, &preset.enabled_ids, &preset.disabled_ids).hash(&mut hasher);
+        }
+        let key = hasher.finish();
+        if self.stale_entries_key == Some(key) {
+            return;
+        }
+        self.stale_entries_key = Some(key);
+
+        let mut stale_entries = vec![];
+        for preset in &prefs.presets {
+            for entry in preset.find_stale_entries(|id| self.font_list_item_map.contains_key(id)) {
+                let suggestion = match &entry {
+                    StaleEntry::Duplicate(_) => None,
+                    StaleEntry::Orphaned(id) => self
+                        .similar_font_ids
+                        .entry(id.clone())
+                        .or_insert_with(|| find_similar_font_id(id, &self.font_list_item_map))
+                        .clone(),
+                };
+                stale_entries.push(StaleEntryWarning {
+                    preset_name: preset.name.clone(),
+                    entry,
+                    suggestion,
+                });
+            }
+        }
+        self.stale_entries = stale_entries;
+    }
+
     fn create_font_entries(&self) -> Vec<FontEntry> {
         self.font_list_item_map
             .values()
@@ -578,12 +2398,152 @@ impl SettingsView {
     }
 }
 
+/// Fonts to export with their files, read from the pack or else from the installed fonts.
+fn specimen_fonts(
+    font_entries: Vec<FontEntry>,
+    font_list_repo: &FontListRepository,
+    font_file_repo: &FontFileRepository,
+) -> Vec<SpecimenFont> {
+    font_entries
+        .into_iter()
+        .map(|entry| SpecimenFont {
+            file: font_list_repo
+                .read_font_file(&entry)
+                .or_else(|| font_file_repo.find_by_name(entry.font_name)),
+            entry,
+        })
+        .collect()
+}
+
+/// Entry of the sample text history dropdown, shown as the first line of the text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleTextHistoryItem(String);
+
+impl std::fmt::Display for SampleTextHistoryItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const MAX_CHARS: usize = 24;
+        let line = self.0.lines().next().unwrap_or_default();
+        if line.chars().count() > MAX_CHARS || self.0.lines().nth(1).is_some() {
+            let line = line.chars().take(MAX_CHARS).collect::<String>();
+            write!(f, "{line}\u{2026}")
+        } else {
+            f.write_str(line)
+        }
+    }
+}
+
+/// Preset being dragged to another position.
+#[derive(Debug)]
+struct PresetDrag {
+    name: String,
+    /// Preset under the cursor, whose position the dragged preset takes when dropped.
+    target: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 struct EditingPreset {
     original_name: String,
     new_name: String,
 }
 
+/// [`StaleEntry`] of a preset, with the catalog entry offered as a replacement.
+#[derive(Clone, Debug)]
+pub struct StaleEntryWarning {
+    preset_name: String,
+    entry: StaleEntry,
+    suggestion: Option<String>,
+}
+
+/// Count and list of font IDs missing from the catalog, e.g. `1 unknown font: Gone`.
+fn describe_unknown_fonts(ids: &[String]) -> String {
+    let noun = if ids.len() == 1 { "font" } else { "fonts" };
+    format!("{} unknown {noun}: {}", ids.len(), ids.join(", "))
+}
+
+/// Finds the catalog entry whose ID, name or path is closest to the orphaned `id`, allowing
+/// one edit per three characters.
+fn find_similar_font_id(
+    id: &str,
+    font_list_item_map: &IndexMap<String, FontListItem>,
+) -> Option<String> {
+    let key = similarity_key(id);
+    let max_distance = key.chars().count() / 3;
+    font_list_item_map
+        .values()
+        .filter_map(|item| {
+            let entry = &item.font_entry;
+            [
+                Some(entry.id.as_str()),
+                Some(entry.font_name),
+                entry.display_name.as_deref(),
+                Some(entry.filepath.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|name| edit_distance(&key, &similarity_key(name)))
+            .min()
+            .map(|distance| (distance, &entry.id))
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, id)| id.clone())
+}
+
+/// Lowercase letters and digits of `name`, without the directory and extension of a font path.
+fn similarity_key(name: &str) -> String {
+    let path = Path::new(name);
+    let is_font_file = path.extension().is_some_and(|ext| {
+        ["otf", "ttc", "ttf"]
+            .iter()
+            .any(|data| ext.eq_ignore_ascii_case(data))
+    });
+    let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) if is_font_file => stem,
+        _ => name,
+    };
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs = rhs.chars().collect::<Vec<_>>();
+    let mut distances = (0..=rhs.len()).collect::<Vec<_>>();
+    for (i, lhs_char) in lhs.chars().enumerate() {
+        let mut diagonal = distances[0];
+        distances[0] = i + 1;
+        for (j, rhs_char) in rhs.iter().enumerate() {
+            let above = distances[j + 1];
+            distances[j + 1] = if lhs_char == *rhs_char {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(distances[j])
+            };
+            diagonal = above;
+        }
+    }
+    distances[rhs.len()]
+}
+
+fn mib_to_bytes(mib: u32) -> usize {
+    mib as usize * 1024 * 1024
+}
+
+fn bytes_to_mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn font_size_row(font_name: &str, bytes: usize) -> Element<'_, SettingsViewCommand> {
+    row![
+        text(font_name).size(12),
+        space().width(Length::Fill),
+        text(format!("{:.1} MiB", bytes_to_mib(bytes))).size(12),
+    ]
+    .spacing(4)
+    .into()
+}
+
 fn send_xmessage(msg: XMessage) -> Task<SettingsViewCommand> {
     Task::done(SettingsViewCommand::SendXMessage(msg))
 }
@@ -671,113 +2631,474 @@ fn divider<'a>() -> Element<'a, SettingsViewCommand> {
 mod tests {
     use super::*;
     use crate::data::font_list::FontListRepository;
+    use crate::model::DEFAULT_SAMPLE_FONT_SIZE;
     use iced::window;
 
     fn create_settings_view() -> SettingsView {
         let project_dirs =
             directories::ProjectDirs::from("com", "sukawasatoru", "fonts66-viewer").unwrap();
         let font_list_repo = Arc::new(FontListRepository::default());
+        let font_file_repo = Arc::new(FontFileRepository::default());
         let prefs_repo = Arc::new(PreferencesRepository::new(&project_dirs));
-        SettingsView::new(font_list_repo, prefs_repo)
+        let sample_text_repo = Arc::new(SampleTextRepository::new(&project_dirs));
+        SettingsView::new(
+            font_list_repo,
+            font_file_repo,
+            prefs_repo,
+            sample_text_repo,
+            std::env::temp_dir(),
+        )
+    }
+
+    fn setup_with_default_prefs() -> SettingsView {
+        let mut view = create_settings_view();
+        let prefs = view.prefs_repo.retrieve().unwrap();
+        let _ = view.update(SettingsViewCommand::PrefsLoaded(prefs));
+        view
+    }
+
+    fn setup_with_prefs(prefs: Preferences) -> SettingsView {
+        let mut view = create_settings_view();
+        let _ = view.update(SettingsViewCommand::PrefsLoaded(prefs));
+        view
+    }
+
+    fn two_presets() -> Preferences {
+        Preferences {
+            presets: vec![
+                Preset {
+                    name: "Preset 1".into(),
+                    template: SpecimenTemplate::with_headline_size(24),
+                    enabled_ids: vec!["Arial".into()],
+                    custom_text: "Title 1".into(),
+                    ..Default::default()
+                },
+                Preset {
+                    name: "Preset 2".into(),
+                    template: SpecimenTemplate::with_headline_size(48),
+                    enabled_ids: vec!["Times New Roman".into()],
+                    custom_text: "Title 2".into(),
+                    sort_mode: SortMode::RenderedWidth,
+                    ..Default::default()
+                },
+            ],
+            sample_text_history: vec![],
+            font_cache_budget_mb: DEFAULT_FONT_CACHE_BUDGET_MB,
+            catalog_version: None,
+            favourite_ids: vec![],
+        }
+    }
+
+    fn two_presets_no_paths() -> Preferences {
+        Preferences {
+            presets: vec![
+                Preset {
+                    name: "Preset 1".into(),
+                    template: SpecimenTemplate::with_headline_size(24),
+                    ..Default::default()
+                },
+                Preset {
+                    name: "Preset 2".into(),
+                    template: SpecimenTemplate::with_headline_size(48),
+                    ..Default::default()
+                },
+            ],
+            sample_text_history: vec![],
+            font_cache_budget_mb: DEFAULT_FONT_CACHE_BUDGET_MB,
+            catalog_version: None,
+            favourite_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn prefs_loaded_enables_only_preset_fonts() {
+        let mut view = create_settings_view();
+
+        // Before PrefsLoaded, all fonts are enabled.
+        assert!(view.font_list_item_map.values().all(|item| item.enabled));
+
+        let prefs = view.prefs_repo.retrieve().unwrap();
+        let _ = view.update(SettingsViewCommand::PrefsLoaded(prefs));
+
+        // After PrefsLoaded, only "Arial" (in fake preset) should be enabled.
+        assert!(view.font_list_item_map.get("Arial").unwrap().enabled);
+        assert!(
+            !view
+                .font_list_item_map
+                .get("Times New Roman")
+                .unwrap()
+                .enabled
+        );
+
+        let entries = view.create_font_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].filepath, "./arial.ttf");
+    }
+
+    #[test]
+    fn specimen_row_size_updated_sets_dirty_and_updates_preset() {
+        let mut view = setup_with_default_prefs();
+        assert_eq!(view.save_prefs_version, 0);
+        assert_eq!(view.template.headline_size(), 24);
+
+        let _ = view.update(SettingsViewCommand::SpecimenRowSizeUpdated(
+            SpecimenRowKind::Headline,
+            48,
+        ));
+
+        assert!(view.save_prefs_version > 0);
+        assert_eq!(view.template.headline_size(), 48);
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0]
+                .template
+                .headline_size(),
+            48
+        );
+    }
+
+    #[test]
+    fn specimen_row_toggled_updates_preset_template() {
+        let mut view = setup_with_default_prefs();
+
+        let _ = view.update(SettingsViewCommand::SpecimenRowToggled(
+            SpecimenRowKind::Body,
+            false,
+        ));
+
+        let template = &view.prefs.as_ref().unwrap().presets[0].template;
+        assert_eq!(template, &view.template);
+        assert!(!template.rows[1].enabled);
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn fit_to_width_toggled_updates_preset_template() {
+        let mut view = setup_with_default_prefs();
+
+        let _ = view.update(SettingsViewCommand::FitToWidthToggled(true));
+
+        assert!(view.template.fit_to_width);
+        assert!(
+            view.prefs.as_ref().unwrap().presets[0]
+                .template
+                .fit_to_width
+        );
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn sort_mode_selected_updates_preset() {
+        let mut view = setup_with_prefs(two_presets());
+
+        let _ = view.update(SettingsViewCommand::SortModeSelected(SortMode::Name));
+
+        assert_eq!(view.sort_mode, SortMode::Name);
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0].sort_mode,
+            SortMode::Name
+        );
+        assert!(view.save_prefs_version > 0);
+
+        let _ = view.update(SettingsViewCommand::PresetSelected("Preset 2".into()));
+        assert_eq!(view.sort_mode, SortMode::RenderedWidth);
+    }
+
+    #[test]
+    fn font_cache_budget_updated_saves_prefs() {
+        let mut view = setup_with_default_prefs();
+
+        let _ = view.update(SettingsViewCommand::FontCacheBudgetUpdated(64));
+
+        assert_eq!(view.prefs.as_ref().unwrap().font_cache_budget_mb, 64);
+        assert_eq!(view.font_cache_status.budget_bytes, mib_to_bytes(64));
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn font_catalog_reconciles_font_list() {
+        let mut view = setup_with_default_prefs();
+        let _ = view.update(SettingsViewCommand::FontListItemChanged(
+            view.font_list_item_map["Times New Roman"]
+                .font_entry
+                .clone(),
+            true,
+        ));
+        let enabled_ids = view.prefs.as_ref().unwrap().presets[0].enabled_ids.clone();
+
+        let task = view.update(SettingsViewCommand::XMessage(XMessage::FontCatalog(
+            FontCatalog {
+                entries: vec![
+                    FontEntry::new("New".into(), "./new.ttf".into(), None, "New"),
+                    FontEntry::new(
+                        "Times New Roman".into(),
+                        "./times.ttf".into(),
+                        None,
+                        "Times New Roman",
+                    ),
+                ],
+                ..Default::default()
+            },
+        )));
+
+        assert_eq!(task.units(), 1);
+        assert_eq!(
+            view.font_list_item_map.keys().collect::<Vec<_>>(),
+            vec!["New", "Times New Roman"],
+        );
+        assert!(!view.font_list_item_map["New"].enabled);
+        assert!(view.font_list_item_map["Times New Roman"].enabled);
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0].enabled_ids,
+            enabled_ids
+        );
     }
 
-    fn setup_with_default_prefs() -> SettingsView {
+    #[test]
+    fn prefs_loaded_marks_fonts_added_since_last_run() {
         let mut view = create_settings_view();
-        let prefs = view.prefs_repo.retrieve().unwrap();
+        let mut prefs = view.prefs_repo.retrieve().unwrap();
+        prefs.catalog_version = Some(1);
+
         let _ = view.update(SettingsViewCommand::PrefsLoaded(prefs));
-        view
+
+        assert!(view.font_list_item_map["Times New Roman"].is_new);
+        assert!(!view.font_list_item_map["Arial"].is_new);
+        assert_eq!(view.prefs.as_ref().unwrap().catalog_version, Some(2));
     }
 
-    fn setup_with_prefs(prefs: Preferences) -> SettingsView {
-        let mut view = create_settings_view();
-        let _ = view.update(SettingsViewCommand::PrefsLoaded(prefs));
-        view
+    #[test]
+    fn deny_list_mode_shows_fonts_added_later() {
+        let mut view = setup_with_default_prefs();
+
+        let _ = view.update(SettingsViewCommand::FontSelectionModeSelected(
+            FontSelectionMode::DenyList,
+        ));
+
+        let preset = &view.prefs.as_ref().unwrap().presets[0];
+        assert!(preset.enabled_ids.is_empty());
+        assert_eq!(preset.disabled_ids, vec!["Times New Roman"]);
+        assert!(view.save_prefs_version > 0);
+
+        let arial = view.font_list_item_map["Arial"].font_entry.clone();
+        let times = view.font_list_item_map["Times New Roman"]
+            .font_entry
+            .clone();
+        let _ = view.update(SettingsViewCommand::XMessage(XMessage::FontCatalog(
+            FontCatalog {
+                entries: vec![
+                    arial,
+                    times,
+                    FontEntry::new("New".into(), "./new.ttf".into(), None, "New"),
+                ],
+                ..Default::default()
+            },
+        )));
+
+        assert!(view.font_list_item_map["Arial"].enabled);
+        assert!(!view.font_list_item_map["Times New Roman"].enabled);
+        assert!(view.font_list_item_map["New"].enabled);
     }
 
-    fn two_presets() -> Preferences {
-        Preferences {
-            presets: vec![
-                Preset {
-                    name: "Preset 1".into(),
-                    font_size: 24,
-                    enable_paths: vec!["./arial.ttf".into()],
-                },
-                Preset {
-                    name: "Preset 2".into(),
-                    font_size: 48,
-                    enable_paths: vec!["./times.ttf".into()],
-                },
-            ],
-        }
+    #[test]
+    fn stale_entries_can_be_remapped_or_cleaned_up() {
+        let mut prefs = two_presets();
+        prefs.presets[0].enabled_ids = ["Arial", "times-new-roman", "Arial", "Gone"]
+            .map(String::from)
+            .to_vec();
+        let mut view = setup_with_prefs(prefs);
+
+        assert_eq!(view.stale_entries.len(), 3);
+        assert!(view.stale_entries.iter().any(|warning| {
+            warning.entry == StaleEntry::Orphaned("Gone".into()) && warning.suggestion.is_none()
+        }));
+        let warning = view
+            .stale_entries
+            .iter()
+            .find(|warning| warning.entry == StaleEntry::Orphaned("times-new-roman".into()))
+            .cloned()
+            .unwrap();
+        assert_eq!(warning.suggestion.as_deref(), Some("Times New Roman"));
+
+        let _ = view.update(SettingsViewCommand::StaleEntryRemapClicked(warning));
+
+        assert!(view.font_list_item_map["Times New Roman"].enabled);
+        assert_eq!(view.stale_entries.len(), 2);
+
+        let _ = view.update(SettingsViewCommand::StaleEntriesCleanupClicked);
+
+        assert!(view.stale_entries.is_empty());
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0].enabled_ids,
+            vec!["Arial", "Times New Roman"],
+        );
+        assert!(view.save_prefs_version > 0);
     }
 
-    fn two_presets_no_paths() -> Preferences {
-        Preferences {
-            presets: vec![
-                Preset {
-                    name: "Preset 1".into(),
-                    font_size: 24,
-                    enable_paths: vec![],
-                },
-                Preset {
-                    name: "Preset 2".into(),
-                    font_size: 48,
-                    enable_paths: vec![],
-                },
-            ],
-        }
+    #[test]
+    fn stale_entries_are_found_again_only_after_ids_or_catalog_change() {
+        let mut prefs = two_presets();
+        prefs.presets[0].enabled_ids = ["Arial", "times-new-roman"].map(String::from).to_vec();
+        let mut view = setup_with_prefs(prefs);
+        assert_eq!(view.stale_entries.len(), 1);
+        view.similar_font_ids
+            .insert("times-new-roman".into(), Some("Arial".into()));
+
+        view.validate_presets();
+        assert_eq!(
+            view.stale_entries[0].suggestion.as_deref(),
+            Some("Times New Roman"),
+        );
+
+        let arial = view.font_list_item_map["Arial"].font_entry.clone();
+        let _ = view.update(SettingsViewCommand::FontListItemChanged(arial, false));
+        assert_eq!(view.stale_entries[0].suggestion.as_deref(), Some("Arial"));
+
+        let entries = view.font_list_repo.find_all();
+        let _ = view.update(SettingsViewCommand::XMessage(XMessage::FontCatalog(
+            FontCatalog {
+                entries,
+                ..Default::default()
+            },
+        )));
+        assert_eq!(
+            view.stale_entries[0].suggestion.as_deref(),
+            Some("Times New Roman"),
+        );
     }
 
     #[test]
-    fn prefs_loaded_enables_only_preset_fonts() {
-        let mut view = create_settings_view();
+    fn preset_import_renames_colliding_presets() {
+        let mut view = setup_with_prefs(two_presets());
+        let mut imported = two_presets().presets;
+        imported[1].name = "太ゴシック".into();
 
-        // Before PrefsLoaded, all fonts are enabled.
-        assert!(view.font_list_item_map.values().all(|item| item.enabled));
+        let _ = view.update(SettingsViewCommand::PresetImportFinished(Ok(imported)));
 
-        let prefs = view.prefs_repo.retrieve().unwrap();
-        let _ = view.update(SettingsViewCommand::PrefsLoaded(prefs));
+        let names = view
+            .prefs
+            .as_ref()
+            .unwrap()
+            .presets
+            .iter()
+            .map(|preset| preset.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["Preset 1", "Preset 2", "Preset 1 Copy", "太ゴシック"]
+        );
+        assert_eq!(
+            view.preset_file_status.as_deref(),
+            Some("Imported 2 presets")
+        );
+        assert!(view.save_prefs_version > 0);
+    }
 
-        // After PrefsLoaded, only "./arial.ttf" (in fake preset) should be enabled.
-        assert!(view.font_list_item_map.get("./arial.ttf").unwrap().enabled);
-        assert!(!view.font_list_item_map.get("./times.ttf").unwrap().enabled);
+    #[test]
+    fn preset_code_paste_reports_unknown_fonts() {
+        let mut view = setup_with_prefs(two_presets());
+        let mut preset = two_presets().presets.remove(0);
+        preset.enabled_ids = vec!["Arial".into(), "Gone".into()];
+        let code = encode_share_code(&preset).unwrap();
+
+        let _ = view.update(SettingsViewCommand::PresetCodePasted(Some(code)));
+
+        let pasted = view.prefs.as_ref().unwrap().presets.last().unwrap();
+        assert_eq!(pasted.name, "Preset 1 Copy");
+        assert_eq!(pasted.enabled_ids, vec!["Arial", "Gone"]);
+        assert_eq!(
+            view.preset_file_status.as_deref(),
+            Some("Pasted Preset 1 Copy with 1 unknown font: Gone"),
+        );
+        assert_eq!(view.stale_entries.len(), 1);
+
+        let _ = view.update(SettingsViewCommand::PresetCodePasted(Some("hello".into())));
+        assert_eq!(view.prefs.as_ref().unwrap().presets.len(), 3);
+    }
 
-        let entries = view.create_font_entries();
-        assert_eq!(entries.len(), 1);
-        assert_eq!(entries[0].filepath, "./arial.ttf");
+    #[test]
+    fn preset_code_copy_reports_unknown_fonts() {
+        let mut prefs = two_presets();
+        prefs.presets[0].enabled_ids = ["Arial", "Gone", "Lost"].map(String::from).to_vec();
+        let mut view = setup_with_prefs(prefs);
+
+        let _ = view.update(SettingsViewCommand::PresetCodeCopyClicked);
+
+        assert_eq!(
+            view.preset_file_status.as_deref(),
+            Some(
+                "Copied the code of Preset 1 to the clipboard without 2 unknown fonts: Gone, Lost"
+            ),
+        );
+    }
+
+    #[test]
+    fn pack_scanned_reloads_only_when_changed() {
+        let mut view = create_settings_view();
+
+        assert_eq!(
+            view.update(SettingsViewCommand::PackScanned(Some(1)))
+                .units(),
+            0
+        );
+        assert_eq!(
+            view.update(SettingsViewCommand::PackScanned(Some(1)))
+                .units(),
+            0
+        );
+        assert_eq!(
+            view.update(SettingsViewCommand::PackScanned(Some(2)))
+                .units(),
+            1
+        );
     }
 
     #[test]
-    fn font_size_updated_sets_dirty_and_updates_preset() {
+    fn layout_updates_preset() {
         let mut view = setup_with_default_prefs();
-        assert_eq!(view.save_prefs_version, 0);
-        assert_eq!(view.font_size, 24);
 
-        let _ = view.update(SettingsViewCommand::FontSizeUpdated(48));
+        let _ = view.update(SettingsViewCommand::LayoutKindSelected(
+            PreviewLayoutKind::Grid,
+        ));
+        let _ = view.update(SettingsViewCommand::GridColumnsUpdated(6));
 
+        let expected = PreviewLayout {
+            kind: PreviewLayoutKind::Grid,
+            grid_columns: 6,
+        };
+        assert_eq!(view.layout, expected);
+        assert_eq!(view.prefs.as_ref().unwrap().presets[0].layout, expected);
         assert!(view.save_prefs_version > 0);
-        assert_eq!(view.font_size, 48);
-        assert_eq!(view.prefs.as_ref().unwrap().presets[0].font_size, 48);
     }
 
     #[test]
     fn save_prefs_requested_clears_dirty_when_dirty() {
         let mut view = setup_with_default_prefs();
-        let _ = view.update(SettingsViewCommand::FontSizeUpdated(48));
+        let _ = view.update(SettingsViewCommand::SpecimenRowSizeUpdated(
+            SpecimenRowKind::Headline,
+            48,
+        ));
         let version = view.save_prefs_version;
         assert!(version > 0);
 
         let _ = view.update(SettingsViewCommand::SavePrefsRequested(version));
         assert_eq!(view.save_prefs_version, 0);
 
-        // prefs in view should have updated font_size
-        assert_eq!(view.prefs.as_ref().unwrap().presets[0].font_size, 48);
+        // prefs in view should have updated headline size
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0]
+                .template
+                .headline_size(),
+            48
+        );
     }
 
     #[test]
     fn close_requested_saves_when_dirty() {
         let mut view = setup_with_default_prefs();
-        let _ = view.update(SettingsViewCommand::FontSizeUpdated(48));
+        let _ = view.update(SettingsViewCommand::SpecimenRowSizeUpdated(
+            SpecimenRowKind::Headline,
+            48,
+        ));
         assert!(view.save_prefs_version > 0);
 
         let _ = view.update(SettingsViewCommand::XMessage(XMessage::CloseRequested(
@@ -786,7 +3107,7 @@ mod tests {
         assert_eq!(view.save_prefs_version, 0);
 
         let saved = view.prefs_repo.retrieve().unwrap();
-        assert_eq!(saved.presets[0].font_size, 48);
+        assert_eq!(saved.presets[0].template.headline_size(), 48);
     }
 
     #[test]
@@ -799,7 +3120,7 @@ mod tests {
         assert_eq!(view.save_prefs_version, 0);
 
         let saved = view.prefs_repo.retrieve().unwrap();
-        assert_eq!(saved.presets[0].font_size, 24);
+        assert_eq!(saved.presets[0].template.headline_size(), 24);
     }
 
     #[test]
@@ -815,16 +3136,27 @@ mod tests {
         let mut view = setup_with_prefs(two_presets());
 
         assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 1"));
-        assert_eq!(view.font_size, 24);
-        assert!(view.font_list_item_map.get("./arial.ttf").unwrap().enabled);
-        assert!(!view.font_list_item_map.get("./times.ttf").unwrap().enabled);
+        assert_eq!(view.template.headline_size(), 24);
+        assert!(view.font_list_item_map.get("Arial").unwrap().enabled);
+        assert!(
+            !view
+                .font_list_item_map
+                .get("Times New Roman")
+                .unwrap()
+                .enabled
+        );
 
         let _ = view.update(SettingsViewCommand::PresetSelected("Preset 2".into()));
 
         assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 2"));
-        assert_eq!(view.font_size, 48);
-        assert!(!view.font_list_item_map.get("./arial.ttf").unwrap().enabled);
-        assert!(view.font_list_item_map.get("./times.ttf").unwrap().enabled);
+        assert_eq!(view.template.headline_size(), 48);
+        assert!(!view.font_list_item_map.get("Arial").unwrap().enabled);
+        assert!(
+            view.font_list_item_map
+                .get("Times New Roman")
+                .unwrap()
+                .enabled
+        );
         assert!(view.save_prefs_version > 0);
     }
 
@@ -838,9 +3170,12 @@ mod tests {
         let prefs = view.prefs.as_ref().unwrap();
         assert_eq!(prefs.presets.len(), 2);
         assert_eq!(prefs.presets[1].name, "Preset 2");
-        assert_eq!(prefs.presets[1].font_size, DEFAULT_SAMPLE_FONT_SIZE);
+        assert_eq!(
+            prefs.presets[1].template.headline_size(),
+            DEFAULT_SAMPLE_FONT_SIZE
+        );
         assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 2"));
-        assert_eq!(view.font_size, DEFAULT_SAMPLE_FONT_SIZE);
+        assert_eq!(view.template.headline_size(), DEFAULT_SAMPLE_FONT_SIZE);
         assert!(view.font_list_item_map.values().all(|item| item.enabled));
         assert!(view.save_prefs_version > 0);
     }
@@ -916,9 +3251,112 @@ mod tests {
         assert_eq!(prefs.presets.len(), 1);
         assert_eq!(prefs.presets[0].name, "Preset 2");
         assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 2"));
-        assert_eq!(view.font_size, 48);
-        assert!(view.font_list_item_map.get("./times.ttf").unwrap().enabled);
+        assert_eq!(view.template.headline_size(), 48);
+        assert!(
+            view.font_list_item_map
+                .get("Times New Roman")
+                .unwrap()
+                .enabled
+        );
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn export_reads_fonts_from_the_pack() {
+        let view = setup_with_prefs(two_presets());
+        let dir = tempfile::tempdir().unwrap();
+        // The fake installed fonts have no files, so the font can only come from the pack.
+        let preset = PresetExport {
+            name: "Preset 1".into(),
+            font_size: 24,
+            fonts: specimen_fonts(
+                view.create_font_entries(),
+                &view.font_list_repo,
+                &view.font_file_repo,
+            ),
+            custom_text: "Title 1".into(),
+        };
+
+        let index = export_html_specimen(dir.path(), &preset).unwrap();
+
+        assert_eq!(
+            std::fs::read(index.with_file_name("fonts").join("Arial.ttf")).unwrap(),
+            b"./arial.ttf"
+        );
+    }
+
+    #[test]
+    fn undo_restores_deleted_preset_and_is_saved() {
+        let mut view = setup_with_prefs(two_presets());
+        assert!(!view.prefs_history.can_undo());
+
+        let _ = view.update(SettingsViewCommand::PresetDeleteClicked("Preset 1".into()));
+        let _ = view.update(SettingsViewCommand::UndoClicked);
+
+        let prefs = view.prefs.as_ref().unwrap();
+        assert_eq!(prefs.presets.len(), 2);
+        assert_eq!(prefs.presets[0].name, "Preset 1");
+        assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 1"));
+        assert_eq!(view.template.headline_size(), 24);
+        assert!(view.font_list_item_map["Arial"].enabled);
+        assert!(view.prefs_history.can_redo());
+
         assert!(view.save_prefs_version > 0);
+        let _ = view.update(SettingsViewCommand::XMessage(XMessage::CloseRequested(
+            window::Id::unique(),
+        )));
+        assert_eq!(view.prefs_repo.retrieve().unwrap().presets.len(), 2);
+
+        let _ = view.update(SettingsViewCommand::RedoClicked);
+        assert_eq!(view.prefs.as_ref().unwrap().presets.len(), 1);
+        assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 2"));
+    }
+
+    #[test]
+    fn undo_keeps_favourites() {
+        let mut view = setup_with_prefs(two_presets());
+
+        let _ = view.update(SettingsViewCommand::PresetDeleteClicked("Preset 1".into()));
+        let _ = view.update(SettingsViewCommand::FontFavouriteToggled(
+            "Arial".into(),
+            true,
+        ));
+        let _ = view.update(SettingsViewCommand::UndoClicked);
+
+        let prefs = view.prefs.as_ref().unwrap();
+        assert_eq!(prefs.presets.len(), 2);
+        assert_eq!(prefs.favourite_ids, vec!["Arial"]);
+    }
+
+    #[test]
+    fn undo_shortcuts_follow_settings_visibility() {
+        let mut view = setup_with_default_prefs();
+        assert!(!view.settings_open);
+
+        let _ = view.update(SettingsViewCommand::XMessage(XMessage::SettingsOpen));
+        assert!(view.settings_open);
+
+        let _ = view.update(SettingsViewCommand::XMessage(XMessage::SettingsClose));
+        assert!(!view.settings_open);
+    }
+
+    #[test]
+    fn undo_reverts_typed_text_at_once() {
+        let mut view = setup_with_prefs(two_presets());
+
+        for c in ['!', '?'] {
+            let _ = view.update(SettingsViewCommand::TextEditorAction(
+                text_editor::Action::Edit(text_editor::Edit::Insert(c)),
+            ));
+        }
+        let _ = view.update(SettingsViewCommand::UndoClicked);
+
+        assert_eq!(view.custom_text_content.text(), "Title 1");
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0].custom_text,
+            "Title 1"
+        );
+        assert!(!view.prefs_history.can_undo());
     }
 
     #[test]
@@ -930,7 +3368,7 @@ mod tests {
         let prefs = view.prefs.as_ref().unwrap();
         assert_eq!(prefs.presets.len(), 1);
         assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 1"));
-        assert_eq!(view.font_size, 24);
+        assert_eq!(view.template.headline_size(), 24);
         assert!(view.save_prefs_version > 0);
     }
 
@@ -955,7 +3393,7 @@ mod tests {
         let prefs = view.prefs.as_ref().unwrap();
         assert_eq!(prefs.presets.len(), 1);
         assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 1"));
-        assert_eq!(view.font_size, 24);
+        assert_eq!(view.template.headline_size(), 24);
     }
 
     #[test]
@@ -1048,11 +3486,17 @@ mod tests {
     #[test]
     fn save_prefs_requested_skips_stale_version() {
         let mut view = setup_with_default_prefs();
-        let _ = view.update(SettingsViewCommand::FontSizeUpdated(48));
+        let _ = view.update(SettingsViewCommand::SpecimenRowSizeUpdated(
+            SpecimenRowKind::Headline,
+            48,
+        ));
         let stale_version = view.save_prefs_version;
 
         // Another update bumps the version.
-        let _ = view.update(SettingsViewCommand::FontSizeUpdated(64));
+        let _ = view.update(SettingsViewCommand::SpecimenRowSizeUpdated(
+            SpecimenRowKind::Headline,
+            64,
+        ));
         let latest_version = view.save_prefs_version;
         assert_ne!(stale_version, latest_version);
 
@@ -1075,8 +3519,199 @@ mod tests {
         assert_eq!(prefs.presets.len(), 3);
         let copy = &prefs.presets[1];
         assert_eq!(copy.name, "Preset 1 Copy");
-        assert_eq!(copy.font_size, 24);
-        assert_eq!(copy.enable_paths, vec!["./arial.ttf"]);
+        assert_eq!(copy.template.headline_size(), 24);
+        assert_eq!(copy.enabled_ids, vec!["Arial"]);
+    }
+
+    #[test]
+    fn builtin_preset_copy_appends_editable_preset() {
+        let mut view = setup_with_prefs(two_presets());
+
+        let _ = view.update(SettingsViewCommand::BuiltinPresetCopyClicked("明朝".into()));
+
+        let prefs = view.prefs.as_ref().unwrap();
+        assert_eq!(prefs.presets.len(), 3);
+        assert_eq!(prefs.presets[2].name, "明朝 Copy");
+        assert_eq!(prefs.presets[2].enabled_ids, vec!["Times New Roman"]);
+        assert_eq!(view.prefs_selected_name.as_deref(), Some("明朝 Copy"));
+        assert!(view.font_list_item_map["Times New Roman"].enabled);
+        assert!(!view.font_list_item_map["Arial"].enabled);
+        assert_eq!(view.builtin_presets.len(), 1);
+    }
+
+    #[test]
+    fn rules_mode_applies_only_current_rule_results() {
+        let mut view = setup_with_prefs(two_presets());
+        let _ = view.update(SettingsViewCommand::FontSelectionModeSelected(
+            FontSelectionMode::Rules,
+        ));
+        let _ = view.update(SettingsViewCommand::RuleAdded(FontRuleKind::Family));
+        let rules = view.prefs.as_ref().unwrap().presets[0].rules.clone();
+        assert_eq!(rules, vec![FontRule::Family(String::new())]);
+
+        // Results of rules that have been edited since are dropped.
+        let _ = view.update(SettingsViewCommand::RulesEvaluated(
+            vec![],
+            vec!["Arial".into(), "Times New Roman".into()],
+        ));
+        assert!(!view.font_list_item_map["Times New Roman"].enabled);
+
+        let _ = view.update(SettingsViewCommand::RulesEvaluated(
+            rules,
+            vec!["Times New Roman".into()],
+        ));
+        assert!(view.font_list_item_map["Times New Roman"].enabled);
+        assert!(!view.font_list_item_map["Arial"].enabled);
+
+        let _ = view.update(SettingsViewCommand::RuleRemoved(0));
+        assert!(view.prefs.as_ref().unwrap().presets[0].rules.is_empty());
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn font_favourite_toggled_updates_favourites() {
+        let mut view = setup_with_prefs(two_presets());
+        let _ = view.update(SettingsViewCommand::FontSelectionModeSelected(
+            FontSelectionMode::Rules,
+        ));
+        let _ = view.update(SettingsViewCommand::RuleAdded(FontRuleKind::Favourite));
+
+        let task = view.update(SettingsViewCommand::FontFavouriteToggled(
+            "Arial".into(),
+            true,
+        ));
+        let _ = view.update(SettingsViewCommand::FontFavouriteToggled(
+            "Times New Roman".into(),
+            true,
+        ));
+        let _ = view.update(SettingsViewCommand::FontFavouriteToggled(
+            "Arial".into(),
+            false,
+        ));
+
+        // The rules are evaluated again along with the save.
+        assert_eq!(task.units(), 2);
+        assert_eq!(
+            view.prefs.as_ref().unwrap().favourite_ids,
+            vec!["Times New Roman"]
+        );
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn preset_set_operation_adds_combined_preset() {
+        let mut view = setup_with_prefs(two_presets());
+        let _ = view.update(SettingsViewCommand::PresetOperandASelected(
+            "Preset 1".into(),
+        ));
+        let _ = view.update(SettingsViewCommand::PresetOperandBSelected(
+            "Preset 2".into(),
+        ));
+
+        let _ = view.update(SettingsViewCommand::PresetSetOperationClicked(
+            PresetSetOperation::Union,
+        ));
+
+        let prefs = view.prefs.as_ref().unwrap();
+        assert_eq!(prefs.presets.len(), 3);
+        assert_eq!(prefs.presets[2].name, "Preset 1 \u{222A} Preset 2");
+        assert_eq!(
+            prefs.presets[2].enabled_ids,
+            vec!["Arial".to_string(), "Times New Roman".to_string()]
+        );
+        assert_eq!(prefs.presets[2].custom_text, "Title 1");
+        assert_eq!(
+            view.prefs_selected_name.as_deref(),
+            Some("Preset 1 \u{222A} Preset 2")
+        );
+        assert!(view.font_list_item_map["Times New Roman"].enabled);
+
+        // Renaming a preset keeps it chosen as an operand.
+        let _ = view.update(SettingsViewCommand::PresetRenameStarted("Preset 2".into()));
+        let _ = view.update(SettingsViewCommand::PresetRenameChanged("Serif".into()));
+        let _ = view.update(SettingsViewCommand::PresetRenameConfirmed);
+        let _ = view.update(SettingsViewCommand::PresetSetOperationClicked(
+            PresetSetOperation::Intersection,
+        ));
+
+        let prefs = view.prefs.as_ref().unwrap();
+        assert_eq!(prefs.presets[3].name, "Preset 1 \u{2229} Serif");
+        assert!(prefs.presets[3].enabled_ids.is_empty());
+    }
+
+    #[test]
+    fn preset_set_operation_uses_results_of_selected_rules_preset() {
+        let mut view = setup_with_prefs(two_presets());
+        let _ = view.update(SettingsViewCommand::FontSelectionModeSelected(
+            FontSelectionMode::Rules,
+        ));
+        let _ = view.update(SettingsViewCommand::RuleAdded(FontRuleKind::Favourite));
+        let rules = view.prefs.as_ref().unwrap().presets[0].rules.clone();
+        let _ = view.update(SettingsViewCommand::RulesEvaluated(
+            rules,
+            vec!["Times New Roman".into()],
+        ));
+        let _ = view.update(SettingsViewCommand::PresetOperandASelected(
+            "Preset 1".into(),
+        ));
+        let _ = view.update(SettingsViewCommand::PresetOperandBSelected(
+            "Preset 2".into(),
+        ));
+
+        let _ = view.update(SettingsViewCommand::PresetSetOperationClicked(
+            PresetSetOperation::Intersection,
+        ));
+
+        let prefs = view.prefs.as_ref().unwrap();
+        assert_eq!(prefs.presets[2].name, "Preset 1 \u{2229} Preset 2");
+        assert_eq!(prefs.presets[2].enabled_ids, vec!["Times New Roman"]);
+
+        // Preset 1 is no longer selected, so its fonts are not known.
+        let _ = view.update(SettingsViewCommand::PresetSetOperationClicked(
+            PresetSetOperation::Union,
+        ));
+        assert_eq!(view.prefs.as_ref().unwrap().presets.len(), 3);
+    }
+
+    #[test]
+    fn preset_drag_moves_preset_to_target() {
+        let mut prefs = two_presets();
+        let mut third = prefs.presets[0].clone();
+        third.name = "Preset 3".into();
+        prefs.presets.push(third);
+        let mut view = setup_with_prefs(prefs);
+
+        let _ = view.update(SettingsViewCommand::PresetDragStarted("Preset 3".into()));
+        let _ = view.update(SettingsViewCommand::PresetDragHovered("Preset 1".into()));
+        let _ = view.view();
+        let _ = view.update(SettingsViewCommand::PresetDragEnded);
+
+        let names = view
+            .prefs
+            .as_ref()
+            .unwrap()
+            .presets
+            .iter()
+            .map(|preset| preset.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Preset 3", "Preset 1", "Preset 2"]);
+        assert!(view.preset_drag.is_none());
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn fonts_arranged_switches_preset_to_custom_order() {
+        let mut view = setup_with_prefs(two_presets());
+
+        let _ = view.update(SettingsViewCommand::XMessage(XMessage::FontsArranged(
+            vec!["Times New Roman".into(), "Arial".into()],
+        )));
+
+        let preset = &view.prefs.as_ref().unwrap().presets[0];
+        assert_eq!(preset.font_order, vec!["Times New Roman", "Arial"]);
+        assert_eq!(preset.sort_mode, SortMode::Custom);
+        assert_eq!(view.sort_mode, SortMode::Custom);
+        assert!(view.save_prefs_version > 0);
     }
 
     #[test]
@@ -1098,7 +3733,7 @@ mod tests {
         let _ = view.update(SettingsViewCommand::PresetCopyClicked("Preset 1".into()));
 
         assert_eq!(view.prefs_selected_name.as_deref(), Some("Preset 1 Copy"));
-        assert_eq!(view.font_size, 24);
+        assert_eq!(view.template.headline_size(), 24);
         assert!(view.save_prefs_version > 0);
     }
 
@@ -1108,15 +3743,19 @@ mod tests {
             presets: vec![
                 Preset {
                     name: "Preset 1".into(),
-                    font_size: 24,
-                    enable_paths: vec![],
+                    template: SpecimenTemplate::with_headline_size(24),
+                    ..Default::default()
                 },
                 Preset {
                     name: "Preset 1 Copy".into(),
-                    font_size: 24,
-                    enable_paths: vec![],
+                    template: SpecimenTemplate::with_headline_size(24),
+                    ..Default::default()
                 },
             ],
+            sample_text_history: vec![],
+            font_cache_budget_mb: DEFAULT_FONT_CACHE_BUDGET_MB,
+            catalog_version: None,
+            favourite_ids: vec![],
         });
 
         let _ = view.update(SettingsViewCommand::PresetCopyClicked("Preset 1".into()));
@@ -1135,4 +3774,212 @@ mod tests {
         assert!(view.prefs.is_none());
         assert_eq!(view.save_prefs_version, 0);
     }
+
+    #[test]
+    fn text_editor_edit_updates_preset_custom_text() {
+        let mut view = setup_with_prefs(two_presets());
+
+        let _ = view.update(SettingsViewCommand::TextEditorAction(
+            text_editor::Action::Edit(text_editor::Edit::Insert('!')),
+        ));
+
+        assert_eq!(view.custom_text_content.text(), "!Title 1");
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0].custom_text,
+            "!Title 1"
+        );
+        assert!(view.save_prefs_version > 0);
+    }
+
+    #[test]
+    fn preset_selected_applies_custom_text_and_remembers_previous() {
+        let mut view = setup_with_prefs(two_presets());
+        assert_eq!(view.custom_text_content.text(), "Title 1");
+
+        let _ = view.update(SettingsViewCommand::PresetSelected("Preset 2".into()));
+
+        assert_eq!(view.custom_text_content.text(), "Title 2");
+        assert_eq!(
+            view.prefs.as_ref().unwrap().sample_text_history,
+            vec!["Title 1"]
+        );
+    }
+
+    #[test]
+    fn save_prefs_requested_remembers_custom_text() {
+        let mut view = setup_with_prefs(two_presets());
+        let _ = view.update(SettingsViewCommand::SpecimenRowSizeUpdated(
+            SpecimenRowKind::Headline,
+            48,
+        ));
+
+        let _ = view.update(SettingsViewCommand::SavePrefsRequested(
+            view.save_prefs_version,
+        ));
+
+        assert_eq!(
+            view.prefs.as_ref().unwrap().sample_text_history,
+            vec!["Title 1"]
+        );
+    }
+
+    #[test]
+    fn sample_text_history_selected_replaces_custom_text() {
+        let mut view = setup_with_prefs(two_presets());
+
+        let _ = view.update(SettingsViewCommand::SampleTextHistorySelected(
+            SampleTextHistoryItem("Old title".into()),
+        ));
+
+        assert_eq!(view.custom_text_content.text(), "Old title");
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0].custom_text,
+            "Old title"
+        );
+        assert!(view.save_prefs_version > 0);
+    }
+
+    fn setup_with_sample_texts() -> SettingsView {
+        let mut view = setup_with_prefs(two_presets());
+        let entries = view.sample_text_repo.find_all();
+        let _ = view.update(SettingsViewCommand::SampleTextsLoaded(entries));
+        view
+    }
+
+    #[test]
+    fn sample_text_selected_replaces_custom_text() {
+        let mut view = setup_with_sample_texts();
+        let entry = view.sample_texts[0].clone();
+
+        let _ = view.update(SettingsViewCommand::SampleTextSelected(entry.clone()));
+
+        assert_eq!(view.custom_text_content.text(), entry.text);
+        assert_eq!(
+            view.prefs.as_ref().unwrap().presets[0].custom_text,
+            entry.text
+        );
+        assert_eq!(view.sample_text_name, "");
+    }
+
+    #[test]
+    fn sample_text_save_adds_and_overwrites_user_defined_entry() {
+        let mut view = setup_with_sample_texts();
+        let builtin_len = view.sample_texts.len();
+
+        let _ = view.update(SettingsViewCommand::SampleTextNameChanged("Mine ".into()));
+        let _ = view.update(SettingsViewCommand::SampleTextSaveClicked);
+        assert_eq!(view.sample_texts.len(), builtin_len + 1);
+
+        let _ = view.update(SettingsViewCommand::SampleTextHistorySelected(
+            SampleTextHistoryItem("Updated".into()),
+        ));
+        let _ = view.update(SettingsViewCommand::SampleTextSaveClicked);
+
+        assert_eq!(view.sample_texts.len(), builtin_len + 1);
+        let entry = view.sample_texts.last().unwrap();
+        assert_eq!(entry.name, "Mine");
+        assert_eq!(entry.text, "Updated");
+        assert!(entry.user_defined);
+    }
+
+    #[test]
+    fn sample_text_save_noop_without_name() {
+        let mut view = setup_with_sample_texts();
+        let len = view.sample_texts.len();
+
+        let task = view.update(SettingsViewCommand::SampleTextSaveClicked);
+
+        assert_eq!(task.units(), 0);
+        assert_eq!(view.sample_texts.len(), len);
+    }
+
+    #[test]
+    fn sample_text_remove_keeps_builtin_entries() {
+        let mut view = setup_with_sample_texts();
+        let builtin = view.sample_texts.clone();
+        let _ = view.update(SettingsViewCommand::SampleTextNameChanged("Mine".into()));
+        let _ = view.update(SettingsViewCommand::SampleTextSaveClicked);
+
+        let _ = view.update(SettingsViewCommand::SampleTextRemoveClicked);
+        assert_eq!(view.sample_texts, builtin);
+
+        let _ = view.update(SettingsViewCommand::SampleTextNameChanged(
+            builtin[0].name.clone(),
+        ));
+        let task = view.update(SettingsViewCommand::SampleTextRemoveClicked);
+        assert_eq!(task.units(), 0);
+        assert_eq!(view.sample_texts, builtin);
+    }
+
+    #[test]
+    fn sample_text_history_item_shows_first_line() {
+        assert_eq!(SampleTextHistoryItem("Title".into()).to_string(), "Title");
+        assert_eq!(
+            SampleTextHistoryItem("Title\nSubtitle".into()).to_string(),
+            "Title\u{2026}"
+        );
+        assert_eq!(
+            SampleTextHistoryItem("あ".repeat(30)).to_string(),
+            format!("{}\u{2026}", "あ".repeat(24))
+        );
+    }
+
+    #[test]
+    fn export_pdf_clicked_sets_running() {
+        let mut view = setup_with_default_prefs();
+        assert!(view.export_status.is_none());
+
+        let _ = view.update(SettingsViewCommand::ExportPdfClicked);
+
+        assert!(matches!(view.export_status, Some(ExportStatus::Running)));
+    }
+
+    #[test]
+    fn export_pdf_clicked_noop_when_no_prefs() {
+        let mut view = create_settings_view();
+
+        let _ = view.update(SettingsViewCommand::ExportPdfClicked);
+
+        assert!(view.export_status.is_none());
+    }
+
+    #[test]
+    fn export_html_clicked_noop_while_running() {
+        let mut view = setup_with_default_prefs();
+        let _ = view.update(SettingsViewCommand::ExportPdfClicked);
+
+        let task = view.update(SettingsViewCommand::ExportHtmlClicked);
+
+        assert_eq!(task.units(), 0);
+        assert!(matches!(view.export_status, Some(ExportStatus::Running)));
+    }
+
+    #[test]
+    fn subset_text_file_changed_updates_path() {
+        let mut view = setup_with_default_prefs();
+
+        let _ = view.update(SettingsViewCommand::SubsetTextFileChanged(
+            "/tmp/title.txt".into(),
+        ));
+
+        assert_eq!(view.subset_text_file, "/tmp/title.txt");
+    }
+
+    #[test]
+    fn export_finished_updates_status() {
+        let mut view = setup_with_default_prefs();
+        let _ = view.update(SettingsViewCommand::ExportPdfClicked);
+
+        let _ = view.update(SettingsViewCommand::ExportFinished(Ok(PathBuf::from(
+            "Preset 1.pdf",
+        )
+        .into())));
+        assert!(matches!(
+            view.export_status,
+            Some(ExportStatus::Finished(_))
+        ));
+
+        let _ = view.update(SettingsViewCommand::ExportFinished(Err("error".into())));
+        assert!(matches!(view.export_status, Some(ExportStatus::Failed(_))));
+    }
 }
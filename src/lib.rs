@@ -17,7 +17,9 @@
 pub mod app;
 pub mod asset;
 pub mod data;
+pub mod export;
 pub mod feature;
 pub mod model;
 pub mod prelude;
+pub mod text_metrics;
 pub mod widget;
@@ -14,17 +14,52 @@
  * limitations under the License.
  */
 
+pub use export_status::{ExportResult, ExportStatus};
+pub use font_cache_status::FontCacheStatus;
+pub use font_catalog::FontCatalog;
 pub use font_entry::FontEntry;
+pub use font_facts::FontFacts;
+pub use font_file::FontFile;
+pub use font_rule::{FontRule, FontRuleKind, find_matching_fonts};
+pub use font_selection_mode::FontSelectionMode;
 pub use preferences::{Preferences, Preset};
+pub use preset_diff::PresetDiff;
+pub use preset_file_format::PresetFileFormat;
+pub use preset_set_operation::PresetSetOperation;
+pub use preview_image::PreviewImage;
+pub use preview_layout::{PreviewLayout, PreviewLayoutKind};
+pub use sample_text::SampleText;
+pub use sort_mode::SortMode;
+pub use specimen_template::{SpecimenRow, SpecimenRowKind, SpecimenTemplate};
 pub use sqlite_user_version::SQLiteUserVersion;
+pub use stale_entry::StaleEntry;
 pub use x_message::XMessage;
 
+mod export_status;
+mod font_cache_status;
+mod font_catalog;
 mod font_entry;
+mod font_facts;
+mod font_file;
+mod font_rule;
+mod font_selection_mode;
 mod preferences;
+mod preset_diff;
+mod preset_file_format;
+mod preset_set_operation;
+mod preview_image;
+mod preview_layout;
+mod sample_text;
+mod sort_mode;
+mod specimen_template;
 mod sqlite_user_version;
+mod stale_entry;
 mod x_message;
 
+pub const DEFAULT_FONT_CACHE_BUDGET_MB: u32 = 256;
 pub const DEFAULT_SAMPLE_FONT_SIZE: u32 = 28;
+pub const DEFAULT_SAMPLE_TEXT: &str = "あのイーハトーヴォのすきとおった風、夏でも底に冷たさをもつ青いそら、うつくしい森で飾られたモリーオ市、郊外のぎらぎらひかる草の波。";
+pub const SAMPLE_TEXT_HISTORY_LIMIT: usize = 20;
 pub const SAVE_PREFS_DEBOUNCE_MILLIS: u64 = 15_000;
 pub const TOOLBAR_HEIGHT: u32 = 36;
 pub const WINDOW_BODY_MARGIN: u32 = 8;
@@ -0,0 +1,67 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::path::PathBuf;
+
+/// Progress of an export started from a view.
+#[derive(Clone, Debug)]
+pub enum ExportStatus {
+    Running,
+    Finished(ExportResult),
+    Failed(String),
+}
+
+/// Output of a finished export.
+#[derive(Clone, Debug)]
+pub struct ExportResult {
+    pub path: PathBuf,
+    /// Extra information shown after the path, e.g. the size saved by subsetting.
+    pub summary: Option<String>,
+}
+
+impl From<PathBuf> for ExportResult {
+    fn from(path: PathBuf) -> Self {
+        Self {
+            path,
+            summary: None,
+        }
+    }
+}
+
+impl From<Result<ExportResult, String>> for ExportStatus {
+    fn from(result: Result<ExportResult, String>) -> Self {
+        match result {
+            Ok(result) => ExportStatus::Finished(result),
+            Err(e) => ExportStatus::Failed(e),
+        }
+    }
+}
+
+impl std::fmt::Display for ExportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportStatus::Running => write!(f, "Exporting..."),
+            ExportStatus::Finished(ExportResult {
+                path,
+                summary: None,
+            }) => write!(f, "Exported to {}", path.display()),
+            ExportStatus::Finished(ExportResult {
+                path,
+                summary: Some(summary),
+            }) => write!(f, "Exported to {} ({summary})", path.display()),
+            ExportStatus::Failed(e) => write!(f, "Export failed: {e}"),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+/// Fonts held by the font cache of [`crate::data::font_list::FontListRepository`], and the
+/// fonts registered with iced, which are not covered by the budget of the cache.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontCacheStatus {
+    pub budget_bytes: usize,
+    pub used_bytes: usize,
+    /// Font names and their sizes in bytes, from the least to the most recently used.
+    pub fonts: Vec<(&'static str, usize)>,
+    /// Font names and the bytes registered with iced, in the order of registration.
+    pub registered_fonts: Vec<(&'static str, usize)>,
+}
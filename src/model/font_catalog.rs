@@ -0,0 +1,28 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::FontEntry;
+use std::collections::HashSet;
+
+/// Catalog read again after the pack directory has changed.
+#[derive(Clone, Debug, Default)]
+pub struct FontCatalog {
+    /// Whole catalog, including the disabled entries.
+    pub entries: Vec<FontEntry>,
+    /// Fonts whose file has been moved or replaced since the previous read. Fonts that have been
+    /// added are not included since they have not been registered yet.
+    pub changed_font_names: HashSet<&'static str>,
+}
@@ -20,8 +20,14 @@ use std::sync::Arc;
 pub struct FontEntry(Arc<InnerFontEntry>);
 
 impl FontEntry {
-    pub fn new(filepath: String, display_name: Option<String>, font_name: &'static str) -> Self {
+    pub fn new(
+        id: String,
+        filepath: String,
+        display_name: Option<String>,
+        font_name: &'static str,
+    ) -> Self {
         Self(Arc::new(InnerFontEntry {
+            id,
             filepath,
             display_name,
             font_name,
@@ -31,6 +37,9 @@ impl FontEntry {
 
 #[derive(Debug)]
 pub struct InnerFontEntry {
+    /// Identifies the font across pack releases, unlike [`Self::filepath`] which changes when a
+    /// folder is renamed. Presets refer to fonts by this ID.
+    pub id: String,
     pub filepath: String,
     pub display_name: Option<String>,
     pub font_name: &'static str,
@@ -0,0 +1,30 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// Properties read from a font file, used by the rules that need more than the catalog.
+#[derive(Clone, Debug, Default)]
+pub struct FontFacts {
+    /// `usWeightClass`, e.g. 400 for regular and 700 for bold.
+    pub weight_class: u16,
+    /// Characters mapped to a glyph, sorted.
+    pub chars: Vec<char>,
+}
+
+impl FontFacts {
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.chars.binary_search(&c).is_ok()
+    }
+}
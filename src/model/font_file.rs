@@ -0,0 +1,41 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+/// Raw font file data resolved for a [`crate::model::FontEntry`].
+#[derive(Clone)]
+pub struct FontFile {
+    pub data: Arc<Vec<u8>>,
+    pub index: u32,
+}
+
+impl FontFile {
+    pub fn new(data: Vec<u8>, index: u32) -> Self {
+        Self {
+            data: Arc::new(data),
+            index,
+        }
+    }
+}
+
+impl std::fmt::Debug for FontFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FontFile")
+            .field("data", &format_args!("{} bytes", self.data.len()))
+            .field("index", &self.index)
+            .finish()
+    }
+}
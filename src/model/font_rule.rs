@@ -0,0 +1,276 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::{FontEntry, FontFacts};
+use regex::Regex;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Condition of a preset in [`crate::model::FontSelectionMode::Rules`]. Fonts matching every
+/// rule of the preset are shown.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontRule {
+    /// Folder of the font file in the pack contains the text, e.g. `桜`.
+    Collection(String),
+    /// Font name or display name contains the text, ignoring case.
+    Family(String),
+    /// `usWeightClass` within the range, inclusive.
+    WeightRange(u16, u16),
+    /// ID, font name or display name matches the regular expression.
+    NameRegex(String),
+    /// At least this percentage of the distinct characters of the custom text have a glyph.
+    GlyphCoverage(u32),
+    /// Every character of the custom text has a glyph.
+    SupportsCustomText,
+    /// Font is starred in [`crate::model::Preferences::favourite_ids`].
+    Favourite,
+}
+
+impl FontRule {
+    pub fn kind(&self) -> FontRuleKind {
+        match self {
+            Self::Collection(_) => FontRuleKind::Collection,
+            Self::Family(_) => FontRuleKind::Family,
+            Self::WeightRange(..) => FontRuleKind::WeightRange,
+            Self::NameRegex(_) => FontRuleKind::NameRegex,
+            Self::GlyphCoverage(_) => FontRuleKind::GlyphCoverage,
+            Self::SupportsCustomText => FontRuleKind::SupportsCustomText,
+            Self::Favourite => FontRuleKind::Favourite,
+        }
+    }
+
+    /// Whether the rule reads [`FontFacts`], which requires the font file.
+    pub fn needs_font_facts(&self) -> bool {
+        matches!(
+            self,
+            Self::WeightRange(..) | Self::GlyphCoverage(_) | Self::SupportsCustomText
+        )
+    }
+
+    /// Whether the result changes with the custom text.
+    pub fn needs_custom_text(&self) -> bool {
+        matches!(self, Self::GlyphCoverage(_) | Self::SupportsCustomText)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FontRuleKind {
+    Collection,
+    Family,
+    WeightRange,
+    NameRegex,
+    GlyphCoverage,
+    SupportsCustomText,
+    Favourite,
+}
+
+impl FontRuleKind {
+    pub const ALL: [Self; 7] = [
+        Self::Collection,
+        Self::Family,
+        Self::WeightRange,
+        Self::NameRegex,
+        Self::GlyphCoverage,
+        Self::SupportsCustomText,
+        Self::Favourite,
+    ];
+
+    /// Rule added by the settings before the user edits it.
+    pub fn default_rule(&self) -> FontRule {
+        match self {
+            Self::Collection => FontRule::Collection(String::new()),
+            Self::Family => FontRule::Family(String::new()),
+            Self::WeightRange => FontRule::WeightRange(100, 900),
+            Self::NameRegex => FontRule::NameRegex(String::new()),
+            Self::GlyphCoverage => FontRule::GlyphCoverage(90),
+            Self::SupportsCustomText => FontRule::SupportsCustomText,
+            Self::Favourite => FontRule::Favourite,
+        }
+    }
+}
+
+impl std::fmt::Display for FontRuleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Collection => "Collection",
+            Self::Family => "Family",
+            Self::WeightRange => "Weight",
+            Self::NameRegex => "Name regex",
+            Self::GlyphCoverage => "Glyph coverage",
+            Self::SupportsCustomText => "Supports custom text",
+            Self::Favourite => "Favourite",
+        })
+    }
+}
+
+/// Returns the IDs of the entries that match every rule, in catalog order. `font_facts` is only
+/// called for entries that pass the rules which don't need it. An invalid regular expression
+/// matches nothing, and so does a rule that needs facts of a font whose file is unavailable.
+pub fn find_matching_fonts(
+    rules: &[FontRule],
+    font_entries: &[FontEntry],
+    favourite_ids: &HashSet<String>,
+    custom_text: &str,
+    font_facts: impl Fn(&FontEntry) -> Option<Arc<FontFacts>>,
+) -> Vec<String> {
+    let regexes = rules
+        .iter()
+        .map(|rule| match rule {
+            FontRule::NameRegex(pattern) => Regex::new(pattern).ok(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let chars = custom_text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<BTreeSet<_>>();
+
+    font_entries
+        .iter()
+        .filter(|entry| {
+            let names = [
+                Some(entry.id.as_str()),
+                Some(entry.font_name),
+                entry.display_name.as_deref(),
+            ];
+            let matches_catalog = rules.iter().zip(&regexes).all(|(rule, regex)| match rule {
+                FontRule::Collection(text) => Path::new(&entry.filepath)
+                    .parent()
+                    .and_then(Path::to_str)
+                    .is_some_and(|collection| collection.contains(text.as_str())),
+                FontRule::Family(text) => {
+                    let text = text.to_lowercase();
+                    names[1..]
+                        .iter()
+                        .flatten()
+                        .any(|name| name.to_lowercase().contains(&text))
+                }
+                FontRule::NameRegex(_) => regex
+                    .as_ref()
+                    .is_some_and(|regex| names.iter().flatten().any(|name| regex.is_match(name))),
+                FontRule::Favourite => favourite_ids.contains(&entry.id),
+                _ => true,
+            });
+            if !matches_catalog {
+                return false;
+            }
+            if !rules.iter().any(FontRule::needs_font_facts) {
+                return true;
+            }
+
+            let Some(facts) = font_facts(entry) else {
+                return false;
+            };
+            let covered = chars.iter().filter(|c| facts.has_glyph(**c)).count();
+            rules.iter().all(|rule| match rule {
+                FontRule::WeightRange(min, max) => (*min..=*max).contains(&facts.weight_class),
+                FontRule::GlyphCoverage(percent) => {
+                    covered * 100 >= chars.len() * *percent as usize
+                }
+                FontRule::SupportsCustomText => covered == chars.len(),
+                _ => true,
+            })
+        })
+        .map(|entry| entry.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<FontEntry> {
+        vec![
+            FontEntry::new(
+                "F66FudeZuisen".into(),
+                "./松/F66FudeZuisen.ttf".into(),
+                Some("F66筆ずいせん".into()),
+                "F66FudeZuisen",
+            ),
+            FontEntry::new(
+                "uVeiwE".into(),
+                "./桜/02_uヴュー(12)/UビューE.ttf".into(),
+                Some("UビューE".into()),
+                "uVeiwE",
+            ),
+            FontEntry::new(
+                "uVeiwR".into(),
+                "./桜/02_uヴュー(12)/UビューR.ttf".into(),
+                Some("UビューR".into()),
+                "uVeiwR",
+            ),
+        ]
+    }
+
+    fn facts(entry: &FontEntry) -> Option<Arc<FontFacts>> {
+        let facts = match entry.id.as_str() {
+            "uVeiwE" => FontFacts {
+                weight_class: 800,
+                chars: vec!['あ', 'い'],
+            },
+            "uVeiwR" => FontFacts {
+                weight_class: 400,
+                chars: vec!['あ'],
+            },
+            _ => return None,
+        };
+        Some(Arc::new(facts))
+    }
+
+    #[test]
+    fn find_matching_fonts_applies_every_rule() {
+        let favourite_ids = HashSet::from(["uVeiwR".to_string()]);
+        let find = |rules: &[FontRule], text| {
+            find_matching_fonts(rules, &entries(), &favourite_ids, text, facts)
+        };
+
+        assert_eq!(find(&[], ""), vec!["F66FudeZuisen", "uVeiwE", "uVeiwR"]);
+        assert_eq!(
+            find(&[FontRule::Collection("桜".into())], ""),
+            vec!["uVeiwE", "uVeiwR"]
+        );
+        assert_eq!(
+            find(&[FontRule::Family("筆".into())], ""),
+            vec!["F66FudeZuisen"]
+        );
+        assert_eq!(
+            find(&[FontRule::NameRegex("E$".into())], ""),
+            vec!["uVeiwE"]
+        );
+        assert!(find(&[FontRule::NameRegex("(".into())], "").is_empty());
+        assert_eq!(find(&[FontRule::WeightRange(700, 900)], ""), vec!["uVeiwE"]);
+        assert_eq!(find(&[FontRule::Favourite], ""), vec!["uVeiwR"]);
+        assert_eq!(
+            find(&[FontRule::SupportsCustomText], "あい あ"),
+            vec!["uVeiwE"]
+        );
+        assert_eq!(
+            find(&[FontRule::GlyphCoverage(50)], "あい"),
+            vec!["uVeiwE", "uVeiwR"]
+        );
+        assert_eq!(
+            find(
+                &[
+                    FontRule::Collection("桜".into()),
+                    FontRule::WeightRange(100, 500)
+                ],
+                ""
+            ),
+            vec!["uVeiwR"]
+        );
+    }
+}
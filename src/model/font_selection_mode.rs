@@ -0,0 +1,26 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+/// How a preset stores which fonts are shown.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FontSelectionMode {
+    /// Stores the enabled fonts, so fonts added to the catalog later are hidden.
+    #[default]
+    AllowList,
+    /// Stores the disabled fonts, so fonts added to the catalog later are shown.
+    DenyList,
+    /// Shows the fonts matching [`crate::model::Preset::rules`], updated with the catalog.
+    Rules,
+}
@@ -14,14 +14,278 @@
  * limitations under the License.
  */
 
-#[derive(Clone, Debug)]
+use crate::model::{
+    DEFAULT_FONT_CACHE_BUDGET_MB, FontRule, FontSelectionMode, PreviewLayout,
+    SAMPLE_TEXT_HISTORY_LIMIT, SortMode, SpecimenTemplate, StaleEntry,
+};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Preferences {
     pub presets: Vec<Preset>,
+
+    /// Sample texts used recently, most recent first.
+    pub sample_text_history: Vec<String>,
+
+    /// Budget in MiB of the cache of font files read from the pack. Fonts registered with iced
+    /// are kept until the app quits regardless of the budget.
+    pub font_cache_budget_mb: u32,
+
+    /// Catalog version seen on the last run, to tell which fonts are new. `None` until the
+    /// first run that records it.
+    pub catalog_version: Option<u32>,
+
+    /// Fonts starred by the user, shared by every preset and matched by [`FontRule::Favourite`].
+    pub favourite_ids: Vec<String>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            presets: vec![],
+            sample_text_history: vec![],
+            font_cache_budget_mb: DEFAULT_FONT_CACHE_BUDGET_MB,
+            catalog_version: None,
+            favourite_ids: vec![],
+        }
+    }
+}
+
+impl Preferences {
+    /// Moves `text` to the front of [`Self::sample_text_history`], dropping the oldest entries
+    /// beyond [`SAMPLE_TEXT_HISTORY_LIMIT`]. Blank texts are not recorded.
+    pub fn push_sample_text_history(&mut self, text: &str) {
+        let text = text.trim_end();
+        if text.trim().is_empty() {
+            return;
+        }
+        self.sample_text_history.retain(|data| data != text);
+        self.sample_text_history.insert(0, text.to_string());
+        self.sample_text_history.truncate(SAMPLE_TEXT_HISTORY_LIMIT);
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Preset {
     pub name: String,
-    pub font_size: u32,
-    pub enable_paths: Vec<String>,
+    pub template: SpecimenTemplate,
+    pub enabled_ids: Vec<String>,
+    pub custom_text: String,
+    pub sort_mode: SortMode,
+    pub layout: PreviewLayout,
+    pub font_selection_mode: FontSelectionMode,
+    /// Fonts hidden in [`FontSelectionMode::DenyList`].
+    pub disabled_ids: Vec<String>,
+    /// Conditions of the fonts shown in [`FontSelectionMode::Rules`].
+    pub rules: Vec<FontRule>,
+    /// Font IDs in the order arranged by the user, for [`SortMode::Custom`]. Fonts not listed
+    /// follow in catalog order.
+    pub font_order: Vec<String>,
+}
+
+impl Preset {
+    /// Whether the font is listed as shown. Always `false` in [`FontSelectionMode::Rules`], whose
+    /// fonts are found by [`crate::model::find_matching_fonts`].
+    pub fn is_font_enabled(&self, id: &str) -> bool {
+        match self.font_selection_mode {
+            FontSelectionMode::AllowList => self.enabled_ids.iter().any(|data| data == id),
+            FontSelectionMode::DenyList => !self.disabled_ids.iter().any(|data| data == id),
+            FontSelectionMode::Rules => false,
+        }
+    }
+
+    /// Lists the font as shown or hidden. Does nothing in [`FontSelectionMode::Rules`].
+    pub fn set_font_enabled(&mut self, id: &str, enabled: bool) {
+        let (ids, listed) = match self.font_selection_mode {
+            FontSelectionMode::AllowList => (&mut self.enabled_ids, enabled),
+            FontSelectionMode::DenyList => (&mut self.disabled_ids, !enabled),
+            FontSelectionMode::Rules => return,
+        };
+        ids.retain(|data| data != id);
+        if listed {
+            ids.push(id.to_string());
+        }
+    }
+
+    /// Finds the IDs in [`Self::enabled_ids`] and [`Self::disabled_ids`] that `is_known` rejects,
+    /// and the IDs listed twice. Each orphaned ID is reported once.
+    pub fn find_stale_entries(&self, is_known: impl Fn(&str) -> bool) -> Vec<StaleEntry> {
+        let mut entries = vec![];
+        for ids in [&self.enabled_ids, &self.disabled_ids] {
+            let mut seen = HashSet::new();
+            for id in ids {
+                let entry = if !is_known(id) {
+                    StaleEntry::Orphaned(id.clone())
+                } else if !seen.insert(id) {
+                    StaleEntry::Duplicate(id.clone())
+                } else {
+                    continue;
+                };
+                if !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries
+    }
+
+    /// Drops the entries reported by [`Self::find_stale_entries`], keeping the first of
+    /// duplicated IDs. The same entries are dropped from [`Self::font_order`].
+    pub fn remove_stale_entries(&mut self, is_known: impl Fn(&str) -> bool) {
+        for ids in [
+            &mut self.enabled_ids,
+            &mut self.disabled_ids,
+            &mut self.font_order,
+        ] {
+            let mut seen = HashSet::new();
+            ids.retain(|id| is_known(id) && seen.insert(id.clone()));
+        }
+    }
+
+    /// Replaces `from` with `to`, dropping `from` where `to` is already listed.
+    pub fn remap_font_id(&mut self, from: &str, to: &str) {
+        for ids in [
+            &mut self.enabled_ids,
+            &mut self.disabled_ids,
+            &mut self.font_order,
+        ] {
+            if !ids.iter().any(|data| data == from) {
+                continue;
+            }
+            let mut seen = false;
+            ids.retain_mut(|data| {
+                if data == from {
+                    *data = to.to_string();
+                }
+                if data != to {
+                    return true;
+                }
+                !std::mem::replace(&mut seen, true)
+            });
+        }
+    }
+
+    /// Replaces [`Self::font_order`] with `ids`. The fonts that are not in `ids`, e.g. the
+    /// hidden ones, stay right after the listed font they followed, so that they come back at
+    /// about the same place once shown again.
+    pub fn arrange_fonts(&mut self, ids: Vec<String>) {
+        let listed = ids.iter().collect::<HashSet<_>>();
+        let mut leading = vec![];
+        let mut followers = HashMap::<&String, Vec<&String>>::new();
+        let mut previous = None;
+        for id in &self.font_order {
+            if listed.contains(id) {
+                previous = Some(id);
+                continue;
+            }
+            match previous {
+                Some(previous) => followers.entry(previous).or_default().push(id),
+                None => leading.push(id),
+            }
+        }
+
+        let mut font_order = leading.into_iter().cloned().collect::<Vec<_>>();
+        for id in &ids {
+            font_order.push(id.clone());
+            font_order.extend(followers.remove(id).into_iter().flatten().cloned());
+        }
+        self.font_order = font_order;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_font_enabled_updates_list_of_mode() {
+        let mut preset = Preset {
+            name: "Preset 1".to_string(),
+            font_selection_mode: FontSelectionMode::DenyList,
+            ..Default::default()
+        };
+        assert!(preset.is_font_enabled("new"));
+
+        preset.set_font_enabled("a", false);
+        assert!(!preset.is_font_enabled("a"));
+        assert_eq!(preset.disabled_ids, vec!["a"]);
+
+        preset.set_font_enabled("a", true);
+        assert!(preset.is_font_enabled("a"));
+        assert!(preset.disabled_ids.is_empty());
+        assert!(preset.enabled_ids.is_empty());
+    }
+
+    #[test]
+    fn stale_entries_are_found_and_removed() {
+        let mut preset = Preset {
+            name: "Preset 1".to_string(),
+            enabled_ids: ["a", "gone", "b", "a", "gone"].map(String::from).to_vec(),
+            font_selection_mode: FontSelectionMode::AllowList,
+            ..Default::default()
+        };
+        let is_known = |id: &str| id != "gone";
+
+        assert_eq!(
+            preset.find_stale_entries(is_known),
+            vec![
+                StaleEntry::Orphaned("gone".to_string()),
+                StaleEntry::Duplicate("a".to_string()),
+            ],
+        );
+
+        let mut remapped = preset.clone();
+        remapped.remap_font_id("gone", "b");
+        assert_eq!(remapped.enabled_ids, vec!["a", "b", "a"]);
+
+        preset.remove_stale_entries(is_known);
+        assert_eq!(preset.enabled_ids, vec!["a", "b"]);
+        assert!(preset.find_stale_entries(is_known).is_empty());
+    }
+
+    #[test]
+    fn arrange_fonts_keeps_unlisted_fonts_after_their_neighbour() {
+        let mut preset = Preset {
+            name: "Preset 1".to_string(),
+            sort_mode: SortMode::Custom,
+            font_selection_mode: FontSelectionMode::DenyList,
+            font_order: ["first", "a", "hidden", "b", "c", "last"]
+                .map(String::from)
+                .to_vec(),
+            ..Default::default()
+        };
+
+        preset.arrange_fonts(["c", "a", "b"].map(String::from).to_vec());
+
+        assert_eq!(
+            preset.font_order,
+            vec!["first", "c", "last", "a", "hidden", "b"]
+        );
+    }
+
+    #[test]
+    fn push_sample_text_history_moves_to_front() {
+        let mut prefs = Preferences::default();
+        prefs.push_sample_text_history("foo");
+        prefs.push_sample_text_history("bar\n");
+        prefs.push_sample_text_history("foo");
+        prefs.push_sample_text_history("  ");
+
+        assert_eq!(prefs.sample_text_history, vec!["foo", "bar"]);
+    }
+
+    #[test]
+    fn push_sample_text_history_drops_oldest() {
+        let mut prefs = Preferences::default();
+        for i in 0..=SAMPLE_TEXT_HISTORY_LIMIT {
+            prefs.push_sample_text_history(&i.to_string());
+        }
+
+        assert_eq!(prefs.sample_text_history.len(), SAMPLE_TEXT_HISTORY_LIMIT);
+        assert_eq!(
+            prefs.sample_text_history[0],
+            SAMPLE_TEXT_HISTORY_LIMIT.to_string()
+        );
+        assert!(!prefs.sample_text_history.contains(&"0".to_string()));
+    }
 }
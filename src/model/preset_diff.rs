@@ -0,0 +1,43 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashSet;
+
+/// Fonts of two presets split by which of them enables them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PresetDiff {
+    pub only_a: Vec<String>,
+    pub only_b: Vec<String>,
+    pub both: Vec<String>,
+}
+
+impl PresetDiff {
+    /// Splits `ids` in their order by whether `a` and `b` contain them.
+    pub fn new<'a>(ids: impl IntoIterator<Item = &'a str>, a: &[String], b: &[String]) -> Self {
+        let set_a = a.iter().map(String::as_str).collect::<HashSet<_>>();
+        let set_b = b.iter().map(String::as_str).collect::<HashSet<_>>();
+        let mut diff = Self::default();
+        for id in ids {
+            match (set_a.contains(id), set_b.contains(id)) {
+                (true, true) => diff.both.push(id.to_string()),
+                (true, false) => diff.only_a.push(id.to_string()),
+                (false, true) => diff.only_b.push(id.to_string()),
+                (false, false) => {}
+            }
+        }
+        diff
+    }
+}
@@ -0,0 +1,77 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashSet;
+
+/// Set operation that combines the fonts of two presets into a new preset.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PresetSetOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl PresetSetOperation {
+    pub const ALL: [Self; 3] = [Self::Union, Self::Intersection, Self::Difference];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Union => "\u{222A}",
+            Self::Intersection => "\u{2229}",
+            Self::Difference => "\u{2212}",
+        }
+    }
+
+    /// Combines the font IDs of `a` and `b`, keeping the order of `a` followed by `b`.
+    pub fn apply(&self, a: &[String], b: &[String]) -> Vec<String> {
+        let set_b = b.iter().collect::<HashSet<_>>();
+        let ids = match self {
+            Self::Union => a.iter().chain(b).collect::<Vec<_>>(),
+            Self::Intersection => a.iter().filter(|id| set_b.contains(id)).collect(),
+            Self::Difference => a.iter().filter(|id| !set_b.contains(id)).collect(),
+        };
+        let mut seen = HashSet::new();
+        ids.into_iter()
+            .filter(|id| seen.insert(*id))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn apply_combines_in_order_without_duplicates() {
+        let a = ids(&["x", "y", "z"]);
+        let b = ids(&["z", "w", "x"]);
+
+        assert_eq!(
+            PresetSetOperation::Union.apply(&a, &b),
+            ids(&["x", "y", "z", "w"])
+        );
+        assert_eq!(
+            PresetSetOperation::Intersection.apply(&a, &b),
+            ids(&["x", "z"])
+        );
+        assert_eq!(PresetSetOperation::Difference.apply(&a, &b), ids(&["y"]));
+    }
+}
@@ -0,0 +1,43 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// How the main view arranges the font entries.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PreviewLayout {
+    pub kind: PreviewLayoutKind,
+
+    /// Number of cards per row in [`PreviewLayoutKind::Grid`]. Kept while the list is shown so
+    /// that switching back restores the grid.
+    pub grid_columns: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PreviewLayoutKind {
+    /// One entry per row with all rows of the specimen template.
+    #[default]
+    List,
+    /// Cards with the font name and the custom text only.
+    Grid,
+}
+
+impl Default for PreviewLayout {
+    fn default() -> Self {
+        Self {
+            kind: PreviewLayoutKind::default(),
+            grid_columns: 4,
+        }
+    }
+}
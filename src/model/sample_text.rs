@@ -0,0 +1,34 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+/// Entry of the sample text library.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleText {
+    pub name: String,
+    pub text: String,
+
+    /// `true` for entries added by the user, `false` for the built-in library.
+    pub user_defined: bool,
+}
+
+impl std::fmt::Display for SampleText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.user_defined {
+            write!(f, "{} *", self.name)
+        } else {
+            f.write_str(&self.name)
+        }
+    }
+}
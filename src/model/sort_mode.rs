@@ -0,0 +1,54 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+/// Order of the entries in the main view.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SortMode {
+    /// Order of `font_list.toml`.
+    #[default]
+    Catalog,
+    Name,
+    /// Folder of the font file in the pack, in catalog order within a folder.
+    Collection,
+    WeightClass,
+    /// Width of the custom text, narrowest first.
+    RenderedWidth,
+    /// Order arranged by dragging the fonts, see [`crate::model::Preset::font_order`].
+    Custom,
+}
+
+impl SortMode {
+    pub const ALL: [Self; 6] = [
+        Self::Catalog,
+        Self::Name,
+        Self::Collection,
+        Self::WeightClass,
+        Self::RenderedWidth,
+        Self::Custom,
+    ];
+}
+
+impl std::fmt::Display for SortMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Catalog => "Catalog order",
+            Self::Name => "Name",
+            Self::Collection => "Collection",
+            Self::WeightClass => "Weight class",
+            Self::RenderedWidth => "Rendered width",
+            Self::Custom => "Custom order",
+        })
+    }
+}
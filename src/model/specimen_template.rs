@@ -0,0 +1,170 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crate::model::{DEFAULT_SAMPLE_FONT_SIZE, DEFAULT_SAMPLE_TEXT};
+
+/// Rows shown for each font entry, from top to bottom.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpecimenTemplate {
+    pub rows: Vec<SpecimenRow>,
+
+    /// Sizes the headline of each font to the largest size that fits the width of the list on
+    /// one line, instead of using the font size of the row.
+    pub fit_to_width: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpecimenRow {
+    pub kind: SpecimenRowKind,
+    pub font_size: u32,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SpecimenRowKind {
+    Headline,
+    Body,
+    Kana,
+    Digits,
+    Latin,
+}
+
+impl SpecimenTemplate {
+    /// Template of preferences written before the template existed, where only the custom text
+    /// was shown at a single size. The other rows keep their default sizes but are disabled.
+    pub fn with_headline_size(font_size: u32) -> Self {
+        let mut template = Self::default();
+        template.set_font_size(SpecimenRowKind::Headline, font_size);
+        for row in &mut template.rows {
+            row.enabled = row.kind == SpecimenRowKind::Headline;
+        }
+        template
+    }
+
+    /// Size of the custom text, which is also used for exports that render a single line.
+    pub fn headline_size(&self) -> u32 {
+        self.rows
+            .iter()
+            .find(|row| row.kind == SpecimenRowKind::Headline)
+            .map(|row| row.font_size)
+            .unwrap_or(DEFAULT_SAMPLE_FONT_SIZE)
+    }
+
+    pub fn set_font_size(&mut self, kind: SpecimenRowKind, font_size: u32) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.kind == kind) {
+            row.font_size = font_size;
+        }
+    }
+
+    pub fn set_enabled(&mut self, kind: SpecimenRowKind, enabled: bool) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.kind == kind) {
+            row.enabled = enabled;
+        }
+    }
+}
+
+impl Default for SpecimenTemplate {
+    fn default() -> Self {
+        Self {
+            rows: SpecimenRowKind::ALL
+                .into_iter()
+                .map(|kind| SpecimenRow {
+                    kind,
+                    font_size: kind.default_font_size(),
+                    enabled: true,
+                })
+                .collect(),
+            fit_to_width: false,
+        }
+    }
+}
+
+impl SpecimenRowKind {
+    pub const ALL: [Self; 5] = [
+        Self::Headline,
+        Self::Body,
+        Self::Kana,
+        Self::Digits,
+        Self::Latin,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Headline => "Headline",
+            Self::Body => "Body",
+            Self::Kana => "Kana",
+            Self::Digits => "Digits / symbols",
+            Self::Latin => "Latin",
+        }
+    }
+
+    /// Fixed text of the row, or `None` for the row that shows the custom text.
+    pub fn sample_text(self) -> Option<&'static str> {
+        match self {
+            Self::Headline => None,
+            Self::Body => Some(DEFAULT_SAMPLE_TEXT),
+            Self::Kana => Some(
+                "あいうえお かきくけこ さしすせそ たちつてと ぁぃぅぇぉっゃゅょ アイウエオ カキクケコ ヴァヴィ",
+            ),
+            Self::Digits => Some("0123456789 ¥1,280 $19.99 12:34 ±5% 「」『』（）【】、。！？…"),
+            Self::Latin => Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ abcdefghijklmnopqrstuvwxyz"),
+        }
+    }
+
+    fn default_font_size(self) -> u32 {
+        match self {
+            Self::Headline => DEFAULT_SAMPLE_FONT_SIZE,
+            Self::Body => 14,
+            Self::Kana | Self::Digits | Self::Latin => 18,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_headline_size_shows_only_headline() {
+        let template = SpecimenTemplate::with_headline_size(64);
+
+        assert_eq!(template.headline_size(), 64);
+        assert_eq!(template.rows.len(), SpecimenRowKind::ALL.len());
+        assert_eq!(
+            template.rows[1].font_size,
+            SpecimenTemplate::default().rows[1].font_size
+        );
+        let enabled = template
+            .rows
+            .iter()
+            .map(|row| row.enabled)
+            .collect::<Vec<_>>();
+        assert_eq!(enabled, vec![true, false, false, false, false]);
+    }
+
+    #[test]
+    fn set_enabled_updates_only_the_row() {
+        let mut template = SpecimenTemplate::default();
+
+        template.set_enabled(SpecimenRowKind::Kana, false);
+
+        let enabled = template
+            .rows
+            .iter()
+            .map(|row| row.enabled)
+            .collect::<Vec<_>>();
+        assert_eq!(enabled, vec![true, true, false, true, true]);
+    }
+}
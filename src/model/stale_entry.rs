@@ -0,0 +1,32 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// Entry of a preset font list that does not match the font catalog.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StaleEntry {
+    /// Listed more than once in the same list.
+    Duplicate(String),
+    /// Not in the catalog, e.g. the font was removed from the pack or its ID changed.
+    Orphaned(String),
+}
+
+impl StaleEntry {
+    pub fn id(&self) -> &str {
+        match self {
+            StaleEntry::Duplicate(id) | StaleEntry::Orphaned(id) => id,
+        }
+    }
+}
@@ -13,16 +13,23 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use crate::model::FontEntry;
+use crate::model::{FontCatalog, FontEntry, PreviewLayout, SortMode, SpecimenTemplate};
 use iced::window;
 
 #[derive(Clone, Debug)]
 pub enum XMessage {
     CloseRequested(window::Id),
     CustomText(String),
+    FontCatalog(FontCatalog),
     FontEntries(Vec<FontEntry>),
-    FontSize(u32),
+    /// Font order of the selected preset, for [`SortMode::Custom`].
+    FontOrder(Vec<String>),
+    /// Font IDs shown in the main view, in the order the user dragged them into.
+    FontsArranged(Vec<String>),
     Init,
+    PreviewLayout(PreviewLayout),
     SettingsClose,
     SettingsOpen,
+    SortMode(SortMode),
+    SpecimenTemplate(SpecimenTemplate),
 }
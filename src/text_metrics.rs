@@ -0,0 +1,110 @@
+/*
+ * Copyright 2026 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::FontFile;
+use crate::prelude::*;
+use rustybuzz::ttf_parser::GlyphId;
+use rustybuzz::{Face, UnicodeBuffer};
+
+/// A shaped glyph in font units.
+#[derive(Clone, Debug)]
+pub struct ShapedGlyph {
+    pub glyph_id: GlyphId,
+    pub cluster: u32,
+    pub x_advance: i32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+}
+
+/// A single line of shaped text. `width` is the sum of the advances in font units.
+#[derive(Clone, Debug, Default)]
+pub struct ShapedLine {
+    pub glyphs: Vec<ShapedGlyph>,
+    pub width: i32,
+}
+
+/// Shapes `text` as a single line. Kerning and other positioning features of the font are
+/// applied by the shaper.
+pub fn shape_line(face: &Face, text: &str) -> ShapedLine {
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    let output = rustybuzz::shape(face, &[], buffer);
+
+    let glyphs = output
+        .glyph_infos()
+        .iter()
+        .zip(output.glyph_positions())
+        .map(|(info, pos)| ShapedGlyph {
+            glyph_id: GlyphId(info.glyph_id as u16),
+            cluster: info.cluster,
+            x_advance: pos.x_advance,
+            x_offset: pos.x_offset,
+            y_offset: pos.y_offset,
+        })
+        .collect::<Vec<_>>();
+    let width = glyphs.iter().map(|glyph| glyph.x_advance).sum();
+
+    ShapedLine { glyphs, width }
+}
+
+/// Returns the width of the widest line of `text` in em, i.e. the width in pixels when set at
+/// 1px.
+pub fn measure_text_em(face: &Face, text: &str) -> f32 {
+    let width = text
+        .lines()
+        .map(|line| shape_line(face, line).width)
+        .max()
+        .unwrap_or(0);
+    width as f32 / face.units_per_em() as f32
+}
+
+/// Shapes `text` and breaks it into lines no wider than `max_width` font units.
+///
+/// Explicit line breaks are kept. Lines are broken between clusters rather than at spaces since
+/// Japanese text has no spaces to break on.
+pub fn shape_wrapped(face: &Face, text: &str, max_width: i32) -> Vec<ShapedLine> {
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let shaped = shape_line(face, paragraph);
+        let mut current = ShapedLine::default();
+        let mut start = 0;
+        while start < shaped.glyphs.len() {
+            let cluster = shaped.glyphs[start].cluster;
+            let end = shaped.glyphs[start..]
+                .iter()
+                .position(|glyph| glyph.cluster != cluster)
+                .map_or(shaped.glyphs.len(), |n| start + n);
+            let cluster_glyphs = &shaped.glyphs[start..end];
+            let cluster_width = cluster_glyphs.iter().map(|g| g.x_advance).sum::<i32>();
+
+            if !current.glyphs.is_empty() && current.width + cluster_width > max_width {
+                lines.push(std::mem::take(&mut current));
+            }
+            current.glyphs.extend_from_slice(cluster_glyphs);
+            current.width += cluster_width;
+            start = end;
+        }
+        lines.push(current);
+    }
+    lines
+}
+
+/// Returns the width of the widest line of `text` set in `file` at 1px, so that the width at
+/// any size is `font_size * width`.
+pub fn measure_text_width(file: &FontFile, text: &str) -> Fallible<f32> {
+    let face = Face::from_slice(&file.data, file.index).context("failed to parse font file")?;
+    Ok(measure_text_em(&face, text))
+}
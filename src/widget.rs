@@ -13,6 +13,8 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+pub use drop_target::*;
 pub use settings_button::*;
 
+mod drop_target;
 mod settings_button;